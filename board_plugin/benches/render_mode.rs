@@ -0,0 +1,126 @@
+//! エンティティ描画とチャンク描画のエンティティ数と更新時間の比較
+//!
+//! ウィンドウを使わずに実行できる
+//! ```sh
+//! cargo bench -p board_plugin --bench render_mode
+//! ```
+use bevy::input::ButtonState;
+use bevy::input::mouse::MouseButtonInput;
+use bevy::prelude::*;
+use bevy::state::app::StatesPlugin;
+use bevy::window::{WindowResized, WindowResolution};
+use board_plugin::BoardPlugin;
+use board_plugin::resources::board_options::{BoardOptions, RenderMode, TileSize};
+use board_plugin::resources::BoardAssets;
+use criterion::{BatchSize, BenchmarkId, Criterion, criterion_group, criterion_main};
+
+const TILE_SIZE: f32 = 4.;
+const MAP_SIZES: [u16; 2] = [100, 250];
+const RENDER_MODES: [RenderMode; 2] = [
+    RenderMode::Entities,
+    RenderMode::Chunked { chunk_size: 32 },
+];
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash, States)]
+struct Running;
+
+fn board_app(render_mode: RenderMode, size: u16) -> App {
+    let mut app = App::new();
+    // ワークスペースでは`file_watcher`が有効になるが、このクレートには`assets`がない
+    let assets = AssetPlugin {
+        watch_for_changes_override: Some(false),
+        ..Default::default()
+    };
+    app.add_plugins((MinimalPlugins, assets, StatesPlugin))
+        .init_asset::<Image>()
+        .add_event::<MouseButtonInput>()
+        .add_event::<WindowResized>()
+        .insert_resource(BoardOptions {
+            map_size: (size, size),
+            bomb_count: size as u32 * size as u32 / 6,
+            tile_size: TileSize::Fixed(TILE_SIZE),
+            render_mode,
+            ..Default::default()
+        })
+        .insert_resource(BoardAssets::default())
+        .insert_state(Running)
+        .add_plugins(BoardPlugin { running_state: Running, loading_state: None });
+    let window_size = size as f32 * TILE_SIZE;
+    let mut window = Window {
+        resolution: WindowResolution::new(window_size, window_size),
+        ..Default::default()
+    };
+    // ボードの中央のタイルを指す
+    window.set_cursor_position(Some(Vec2::splat(window_size / 2.)));
+    app.world_mut().spawn(window);
+    app.update();
+    app
+}
+
+/// 右クリックで中央のタイルの旗を切り替えて1フレーム進める
+fn toggle_flag(app: &mut App) {
+    let window = app
+        .world_mut()
+        .query_filtered::<Entity, With<Window>>()
+        .single(app.world());
+    app.world_mut().send_event(MouseButtonInput {
+        button: MouseButton::Right,
+        state: ButtonState::Pressed,
+        window,
+    });
+    app.update();
+}
+
+fn mode_name(render_mode: RenderMode) -> &'static str {
+    match render_mode {
+        RenderMode::Entities => "entities",
+        RenderMode::Chunked { .. } => "chunked",
+    }
+}
+
+fn entity_counts() {
+    for size in MAP_SIZES {
+        for render_mode in RENDER_MODES {
+            let app = board_app(render_mode, size);
+            println!(
+                "{}x{} {}: {} entities",
+                size,
+                size,
+                mode_name(render_mode),
+                app.world().entities().len()
+            );
+        }
+    }
+}
+
+fn spawn_board(c: &mut Criterion) {
+    entity_counts();
+    let mut group = c.benchmark_group("spawn_board");
+    group.sample_size(10);
+    for size in MAP_SIZES {
+        for render_mode in RENDER_MODES {
+            group.bench_with_input(
+                BenchmarkId::new(mode_name(render_mode), size),
+                &size,
+                |b, &size| b.iter_batched(|| (), |_| board_app(render_mode, size), BatchSize::PerIteration),
+            );
+        }
+    }
+    group.finish();
+}
+
+fn update_tile(c: &mut Criterion) {
+    let mut group = c.benchmark_group("update_tile");
+    for size in MAP_SIZES {
+        for render_mode in RENDER_MODES {
+            let mut app = board_app(render_mode, size);
+            group.bench_function(BenchmarkId::new(mode_name(render_mode), size), |b| {
+                b.iter(|| toggle_flag(&mut app))
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, spawn_board, update_tile);
+criterion_main!(benches);
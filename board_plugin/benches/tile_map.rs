@@ -0,0 +1,89 @@
+//! 100万マスのボードでの`TileMap`と`Board`の状態操作
+//!
+//! ```sh
+//! cargo bench -p board_plugin --bench tile_map
+//! ```
+use bevy::prelude::{Entity, Vec2};
+use board_plugin::bounds::Bounds2;
+use board_plugin::components::Coordinates;
+use board_plugin::resources::board::Board;
+use board_plugin::resources::board_options::RenderMode;
+use board_plugin::resources::grid_shape::GridShape;
+use board_plugin::resources::tile_map::TileMap;
+use criterion::{BatchSize, Criterion, black_box, criterion_group, criterion_main};
+
+const SIZE: u16 = 1000;
+
+/// 一般的な上級と同じくらいの約16%の密度
+const BOMBS: u32 = SIZE as u32 * SIZE as u32 / 6;
+
+fn board(bomb_count: u32) -> Board {
+    let mut tile_map = TileMap::empty(SIZE, SIZE);
+    tile_map.set_bombs(bomb_count, 1);
+    let tile_entities = vec![Entity::PLACEHOLDER; tile_map.len()];
+    Board::new(
+        tile_map,
+        Bounds2 {
+            position: Vec2::ZERO,
+            size: Vec2::splat(SIZE as f32),
+        },
+        1.,
+        Entity::PLACEHOLDER,
+        RenderMode::Entities,
+        GridShape::Square,
+        tile_entities,
+    )
+}
+
+fn set_bombs(c: &mut Criterion) {
+    let mut group = c.benchmark_group("tile_map");
+    group.sample_size(10);
+    group.bench_function("set_bombs", |b| {
+        b.iter(|| {
+            let mut tile_map = TileMap::empty(SIZE, SIZE);
+            tile_map.set_bombs(BOMBS, 1);
+            tile_map
+        })
+    });
+    group.bench_function("iter", |b| {
+        let tile_map = board(BOMBS).tile_map;
+        b.iter(|| tile_map.iter().filter(|(_, tile)| tile.is_bomb()).count())
+    });
+    group.finish();
+}
+
+fn board_state(c: &mut Criterion) {
+    let mut group = c.benchmark_group("board");
+    group.sample_size(10);
+    group.bench_function("uncover_region", |b| {
+        // 爆弾が1つだけなのでほぼ全てのマスが1回で開く
+        b.iter_batched(
+            || board(1),
+            |mut board| board.uncover_region(Coordinates { x: SIZE / 2, y: SIZE / 2 }).len(),
+            BatchSize::LargeInput,
+        )
+    });
+    group.bench_function("toggle_mark", |b| {
+        let mut board = board(BOMBS);
+        b.iter(|| {
+            for x in 0..SIZE {
+                board.try_cycle_mark(&Coordinates { x, y: x });
+            }
+            black_box(board.marked_tiles().count())
+        })
+    });
+    group.bench_function("is_covered", |b| {
+        let board = board(BOMBS);
+        b.iter(|| {
+            board
+                .tile_map
+                .iter()
+                .filter(|(coords, _)| board.is_covered(coords) && !board.is_marked(coords))
+                .count()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, set_bombs, board_state);
+criterion_main!(benches);
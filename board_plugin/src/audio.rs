@@ -0,0 +1,54 @@
+//! テーマの音を鳴らすプラグイン、`audio`フィーチャーで有効になる
+//!
+//! ボードのシステムは`SoundCueEvent`を送るだけなので、このプラグインがなくてもゲームは動く
+use bevy::prelude::*;
+use crate::events::SoundCueEvent;
+use crate::resources::sound::{cue_speed, SoundCue};
+use crate::resources::BoardAssets;
+
+/// 開く音のスケールを最初の高さに戻すまでの間隔の秒数
+const SEQUENCE_RESET: f32 = 1.;
+
+/// `SoundCueEvent`を`BoardAssets::sounds`の音で鳴らす
+pub struct BoardAudioPlugin;
+
+impl Plugin for BoardAudioPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, play_sound_cues.run_if(resource_exists::<BoardAssets>));
+    }
+}
+
+/// 開く音を続けて鳴らした回数と最後に鳴らした時刻
+#[derive(Debug, Default)]
+struct RevealSequence {
+    count: usize,
+    last: f32,
+}
+
+fn play_sound_cues(
+    mut commands: Commands,
+    time: Res<Time>,
+    asset_server: Res<AssetServer>,
+    board_assets: Res<BoardAssets>,
+    mut sound_cue_evr: EventReader<SoundCueEvent>,
+    mut sequence: Local<RevealSequence>,
+) {
+    for event in sound_cue_evr.read() {
+        let reveal = matches!(event.cue, SoundCue::Reveal | SoundCue::Cascade);
+        if reveal && time.elapsed_seconds() - sequence.last > SEQUENCE_RESET {
+            sequence.count = 0;
+        }
+        let speed = cue_speed(event.cue, sequence.count, event.tiles);
+        if reveal {
+            sequence.count += 1;
+            sequence.last = time.elapsed_seconds();
+        }
+        let Some(path) = board_assets.sounds.get(&event.cue) else {
+            continue;
+        };
+        commands.spawn(AudioBundle {
+            source: asset_server.load(path.clone()),
+            settings: PlaybackSettings::DESPAWN.with_speed(speed),
+        });
+    }
+}
@@ -0,0 +1,28 @@
+use bevy::prelude::{Component, Vec2};
+#[cfg(feature = "debug")]
+use {
+    bevy_inspector_egui::InspectorOptions,
+};
+
+/// 開いたタイルのカバー、`delay`秒待ってから縮めて消す
+#[cfg_attr(feature = "debug", derive(InspectorOptions))]
+#[derive(Debug, Copy, Clone, PartialEq, Component)]
+pub struct CoverReveal {
+    pub delay: f32,
+    pub elapsed: f32,
+}
+
+/// 立てた旗、少し大きくしてから元の大きさに戻す
+#[cfg_attr(feature = "debug", derive(InspectorOptions))]
+#[derive(Debug, Copy, Clone, Default, PartialEq, Component)]
+pub struct PopIn {
+    pub elapsed: f32,
+}
+
+/// 爆発の破片、飛びながら小さくなって消える
+#[cfg_attr(feature = "debug", derive(InspectorOptions))]
+#[derive(Debug, Copy, Clone, PartialEq, Component)]
+pub struct Debris {
+    pub velocity: Vec2,
+    pub elapsed: f32,
+}
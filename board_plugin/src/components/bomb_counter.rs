@@ -0,0 +1,16 @@
+use bevy::prelude::Component;
+#[cfg(feature = "debug")]
+use {
+    bevy_inspector_egui::InspectorOptions,
+};
+
+/// 爆弾の数の表示、子に数字のスプライトかテキストを持つ
+///
+/// テーマが変わったら子だけを作り直す
+#[cfg_attr(feature = "debug", derive(InspectorOptions))]
+#[derive(Debug, Copy, Clone, PartialEq, Component)]
+pub struct BombCounter {
+    pub count: u8,
+    /// 数字の高さ
+    pub size: f32,
+}
@@ -0,0 +1,49 @@
+use bevy::prelude::Component;
+#[cfg(feature = "debug")]
+use {
+    bevy_inspector_egui::InspectorOptions,
+};
+use crate::components::Coordinates;
+
+/// 複数のタイルを1枚のテクスチャにまとめて描画するチャンク
+#[cfg_attr(feature = "debug", derive(InspectorOptions))]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Component)]
+pub struct TileChunk {
+    /// チャンクの左下のタイル
+    pub origin: Coordinates,
+    pub width: u16,
+    pub height: u16,
+    /// タイルの間に隙間を描画するか
+    pub padded: bool,
+}
+
+impl TileChunk {
+    /// チャンクに含まれるタイルの座標
+    pub fn coordinates(&self) -> impl Iterator<Item = Coordinates> + '_ {
+        (self.origin.y..self.origin.y + self.height).flat_map(move |y| {
+            (self.origin.x..self.origin.x + self.width).map(move |x| Coordinates { x, y })
+        })
+    }
+}
+
+/// テクスチャの再描画が必要なチャンク
+#[cfg_attr(feature = "debug", derive(InspectorOptions))]
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Component)]
+pub struct ChunkRedraw;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn coordinates_cover_the_chunk_row_by_row() {
+        let chunk = TileChunk {
+            origin: Coordinates { x: 4, y: 2 },
+            width: 2,
+            height: 3,
+            padded: false,
+        };
+        let coordinates: Vec<_> = chunk.coordinates().map(|c| (c.x, c.y)).collect();
+        assert_eq!(coordinates, vec![(4, 2), (5, 2), (4, 3), (5, 3), (4, 4), (5, 4)]);
+    }
+}
@@ -1,6 +1,7 @@
 use std::fmt::{Display, Formatter};
 use std::ops::{Add, Sub};
 use bevy::prelude::Component;
+use serde::{Deserialize, Serialize};
 
 #[cfg(feature = "debug")]
 use {
@@ -8,12 +9,22 @@ use {
 };
 
 #[cfg_attr(feature = "debug", derive(InspectorOptions))]
-#[derive(Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Component)]
+#[derive(Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Component, Serialize, Deserialize)]
 pub struct Coordinates {
     pub x: u16,
     pub y: u16,
 }
 
+impl Coordinates {
+    /// 差分を足した座標、`u16`の範囲を超える場合は`None`
+    pub fn checked_offset(self, (x, y): (i8, i8)) -> Option<Self> {
+        Some(Self {
+            x: self.x.checked_add_signed(x as i16)?,
+            y: self.y.checked_add_signed(y as i16)?,
+        })
+    }
+}
+
 impl Add for Coordinates {
     type Output = Self;
 
@@ -25,19 +36,6 @@ impl Add for Coordinates {
     }
 }
 
-impl Add<(i8, i8)> for Coordinates {
-    type Output = Self;
-
-    fn add(self, (x, y): (i8, i8)) -> Self::Output {
-        let x = ((self.x as i16) + x as i16) as u16;
-        let y = ((self.y as i16) + y as i16) as u16;
-        Self {
-            x,
-            y,
-        }
-    }
-}
-
 impl Sub for Coordinates {
     type Output = Self;
 
@@ -0,0 +1,19 @@
+use bevy::prelude::{Component, Entity};
+#[cfg(feature = "debug")]
+use {
+    bevy_inspector_egui::InspectorOptions,
+};
+use crate::components::Coordinates;
+
+/// 端が繋がったボードで、反対側のタイルをボードの外側に表示する複製
+#[cfg_attr(feature = "debug", derive(InspectorOptions))]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Component)]
+pub struct Ghost {
+    /// 複製元のタイル
+    pub source: Coordinates,
+    /// ボードの大きさを単位とした複製元からのずれ
+    pub offset: (i8, i8),
+    /// 爆弾か数字のエンティティ
+    pub content: Option<Entity>,
+    pub flag: Entity,
+}
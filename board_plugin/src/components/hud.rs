@@ -0,0 +1,10 @@
+use bevy::prelude::Component;
+#[cfg(feature = "debug")]
+use {
+    bevy_inspector_egui::InspectorOptions,
+};
+
+/// ウィンドウの左上に表示するゲームの状態のテキスト
+#[cfg_attr(feature = "debug", derive(InspectorOptions))]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default, Component)]
+pub struct Hud;
@@ -1,9 +1,24 @@
 pub use coordinates::*;
+pub use animation::{CoverReveal, Debris, PopIn};
 pub use bomb::Bomb;
+pub use bomb_counter::BombCounter;
 pub use bomb_neighbor::BombNeighbor;
+pub use chunk::{ChunkRedraw, TileChunk};
+pub use ghost::Ghost;
+pub use hud::Hud;
+pub use primary_board::PrimaryBoard;
+pub use restart_button::RestartButton;
+pub use theme_part::ThemePart;
 
 
 mod coordinates;
+mod animation;
 mod bomb;
+mod bomb_counter;
 mod bomb_neighbor;
-pub mod uncover;
\ No newline at end of file
+mod chunk;
+mod ghost;
+mod hud;
+mod primary_board;
+mod restart_button;
+mod theme_part;
\ No newline at end of file
@@ -0,0 +1,12 @@
+use bevy::prelude::Component;
+#[cfg(feature = "debug")]
+use {
+    bevy_inspector_egui::InspectorOptions,
+};
+
+/// `BoardPlugin`が`BoardOptions`から生成したボード
+///
+/// HUD、ハイスコア、デイリーチャレンジ、ネットワーク対戦はこのボードだけを対象にする
+#[cfg_attr(feature = "debug", derive(InspectorOptions))]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default, Component)]
+pub struct PrimaryBoard;
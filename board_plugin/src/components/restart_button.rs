@@ -0,0 +1,10 @@
+use bevy::prelude::Component;
+#[cfg(feature = "debug")]
+use {
+    bevy_inspector_egui::InspectorOptions,
+};
+
+/// HUDの上の中央の顔のボタン、クリックすると新しいボードにする
+#[cfg_attr(feature = "debug", derive(InspectorOptions))]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default, Component)]
+pub struct RestartButton;
@@ -0,0 +1,20 @@
+use bevy::prelude::Component;
+#[cfg(feature = "debug")]
+use {
+    bevy_inspector_egui::InspectorOptions,
+};
+
+/// テーマで見た目が決まるスプライトとテキスト、テーマが変わったら付け直す
+#[cfg_attr(feature = "debug", derive(InspectorOptions))]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Component)]
+pub enum ThemePart {
+    Background,
+    Tile,
+    Cover,
+    Bomb,
+    Flag,
+    /// 対戦モードのプレイヤーの旗
+    PlayerFlag(u8),
+    /// 爆弾の数の色の数字
+    Counter(u8),
+}
@@ -1,14 +1,129 @@
-use bevy::prelude::Event;
+//! ボードのイベント、`BoardAssetsFailedEvent`以外は対象のボードのエンティティを持つ
+//!
+//! `BoardAssetsFailedEvent`は全てのボードで共有する見た目についてのイベント
+use bevy::prelude::{Entity, Event};
 use crate::components::Coordinates;
+use crate::resources::board_options::BoardOptions;
+use crate::resources::sound::SoundCue;
 
+/// ボードを生成した、同じシードと`options`で同じボードを作り直せる
+#[derive(Debug, Clone, Event)]
+pub struct GameStartedEvent {
+    pub board: Entity,
+    pub seed: u64,
+    pub options: BoardOptions,
+}
+
+/// `board`のボードのタイルを開く
+#[derive(Debug, Copy, Clone, Event)]
+pub struct TileTriggerEvent {
+    pub board: Entity,
+    pub coordinates: Coordinates,
+    /// プレイヤーではなくボードの生成時に安全なタイルを開いた
+    pub safe_start: bool,
+}
+
+#[derive(Debug, Copy, Clone, Event)]
+pub struct BoardCompletedEvent {
+    pub board: Entity,
+}
+
+/// 爆弾を開いた、`coordinates`は爆発したタイル
+#[derive(Debug, Copy, Clone, Event)]
+pub struct BombExplosionEvent {
+    pub board: Entity,
+    pub coordinates: Coordinates,
+}
+
+/// `coordinates`を開いて、繋がった範囲の`count`枚のタイルが開いた
+#[derive(Debug, Copy, Clone, Event)]
+pub struct TilesRevealedEvent {
+    pub board: Entity,
+    pub coordinates: Coordinates,
+    pub count: usize,
+    /// ボードの生成時に安全なタイルを開いた
+    pub safe_start: bool,
+}
+
+/// `board`のボードのタイルの旗を切り替える
 #[derive(Debug, Copy, Clone, Event)]
-pub struct TileTriggerEvent(pub Coordinates);
+pub struct TileMarkEvent {
+    pub board: Entity,
+    pub coordinates: Coordinates,
+}
 
+/// 旗を立てたか外した
 #[derive(Debug, Copy, Clone, Event)]
-pub struct BoardCompletedEvent;
+pub struct FlagChangedEvent {
+    pub board: Entity,
+    pub coordinates: Coordinates,
+    /// タイルの新しい旗の数、0なら外した
+    pub level: u8,
+    /// ボード全体の旗の合計の数
+    pub total: usize,
+}
 
+/// 対戦モードでプレイヤーが爆弾を見つけた
 #[derive(Debug, Copy, Clone, Event)]
-pub struct BombExplosionEvent;
+pub struct MineClaimedEvent {
+    pub board: Entity,
+    pub coordinates: Coordinates,
+    pub player: u8,
+}
 
+/// 対戦モードで手番が移った、`player`は新しい手番のプレイヤー
 #[derive(Debug, Copy, Clone, Event)]
-pub struct TileMarkEvent(pub Coordinates);
\ No newline at end of file
+pub struct TurnChangedEvent {
+    pub board: Entity,
+    pub player: u8,
+}
+
+/// 対戦モードの勝敗が決まった、引き分けなら`winner`は`None`
+#[derive(Debug, Copy, Clone, Event)]
+pub struct FlagsMatchEndedEvent {
+    pub board: Entity,
+    pub winner: Option<u8>,
+}
+
+/// テーマか`BoardAssets`のアセットの読み込みに失敗した、代わりの見た目で続ける
+#[derive(Debug, Clone, Event)]
+pub struct BoardAssetsFailedEvent {
+    pub path: String,
+    pub error: String,
+}
+
+/// `board`のボードで鳴らす音、音声の出力はこのイベントを読むプラグインに任せる
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Event)]
+pub struct SoundCueEvent {
+    pub board: Entity,
+    pub cue: SoundCue,
+    /// 開いたマスの数、開く音以外は1
+    pub tiles: usize,
+}
+
+impl SoundCueEvent {
+    pub fn new(board: Entity, cue: SoundCue) -> Self {
+        Self { board, cue, tiles: 1 }
+    }
+
+    /// 開いたマスの数で1つだけか範囲かを決める
+    pub fn reveal(board: Entity, tiles: usize) -> Self {
+        let cue = if tiles > 1 { SoundCue::Cascade } else { SoundCue::Reveal };
+        Self { board, cue, tiles }
+    }
+}
+
+/// `board`のボードを同じシードと配置でやり直す
+#[derive(Debug, Copy, Clone, Event)]
+pub struct RestartBoardEvent {
+    pub board: Entity,
+}
+
+/// `board`のボードを新しい配置で作り直す
+///
+/// `options`がなければ`BoardOptions`のリソースをシードなしで使う
+#[derive(Debug, Clone, Event)]
+pub struct NewBoardEvent {
+    pub board: Entity,
+    pub options: Option<BoardOptions>,
+}
@@ -1,266 +1,672 @@
 use bevy::app::App;
 use bevy::prelude::*;
-use bevy::utils::HashMap;
-use crate::components::{Bomb, BombNeighbor, Coordinates};
-use crate::resources::board_options::{BoardOptions, BoardPosition, TileSize};
+use bevy::state::state::FreelyMutableState;
+use std::time::Duration;
+use crate::components::{Bomb, BombNeighbor, ChunkRedraw, Coordinates, Ghost, PrimaryBoard, ThemePart, TileChunk};
+use crate::resources::board_options::{BoardOptions, GameMode, RenderMode};
+use crate::resources::grid_shape::GridShape;
 use crate::resources::tile_map::TileMap;
 
 #[cfg(feature = "debug")]
 use bevy_inspector_egui::quick::ResourceInspectorPlugin;
 use crate::bounds::Bounds2;
-use crate::components::uncover::{trigger_event_handler, Uncover, uncover_tiles};
-use crate::events::{BoardCompletedEvent, BombExplosionEvent, TileMarkEvent, TileTriggerEvent};
+use crate::events::{
+    BoardAssetsFailedEvent, BoardCompletedEvent, BombExplosionEvent, FlagChangedEvent, FlagsMatchEndedEvent,
+    GameStartedEvent, MineClaimedEvent, NewBoardEvent, RestartBoardEvent, SoundCueEvent, TileMarkEvent,
+    TileTriggerEvent, TilesRevealedEvent, TurnChangedEvent,
+};
 use crate::resources::board::Board;
+use crate::resources::daily::{DailyChallenge, DailyResults, DAILY_RESULTS_PATH};
+use crate::net::client::NetClient;
+use crate::resources::flags_match::FlagsMatch;
+use crate::resources::high_scores::{HighScores, HIGH_SCORES_PATH};
+use crate::resources::settings::{BoardSettings, SETTINGS_PATH};
+use crate::resources::default_visuals::insert_default_visuals;
+use crate::resources::theme::{BoardTheme, Theme, ThemeLoader};
 use crate::resources::BoardAssets;
 use crate::resources::tile::Tile;
+use crate::states::{game_running, BoardState, GamePhase};
+use crate::systems::daily::{finish_daily, record_daily_replay, start_daily_replay};
+use crate::systems::flags::flags_uncover_tiles;
+use crate::systems::ghost::{sync_ghosts, GHOST_ALPHA};
+use crate::systems::hud::{
+    click_restart_button, despawn_hud, position_hud, spawn_hud, update_hud, update_restart_button,
+};
+use crate::systems::counter::spawn_bomb_count;
+use crate::systems::animation::{animate_covers, animate_debris, animate_flags, shake_board};
+use crate::systems::chunk::{chunk_image, chunked_rendering, mark_chunk_tiles, redraw_chunks};
 use crate::systems::input::input_handling;
+use crate::systems::loading::board_assets_loaded;
 use crate::systems::mark::mark_tiles;
+use crate::systems::net::apply_server_messages;
+use crate::systems::phase::{advance_game_phase, pause_time, resume_time};
+use crate::systems::resize::resize_board;
+use crate::systems::restart::restart_board;
+use crate::systems::score::record_high_score;
+use crate::systems::settings::save_settings;
+use crate::systems::theme::{apply_theme, restyle_board};
+use crate::systems::uncover::uncover_tiles;
 
 pub mod resources;
 pub mod components;
-mod bounds;
+pub mod bounds;
+pub mod net;
+#[cfg(feature = "audio")]
+pub mod audio;
+pub mod events;
+pub mod states;
 mod systems;
-mod events;
+
+/// ボードのシステムの順番、外のシステムはこの前後に並べられる
+///
+/// `Input`、`Logic`、`Presentation`の順に動く
+#[derive(Debug, Clone, PartialEq, Eq, Hash, SystemSet)]
+pub enum BoardSet {
+    /// クリックとサーバーのメッセージを`TileTriggerEvent`と`TileMarkEvent`にする
+    Input,
+    /// タイルを開き、旗を立て、結果のイベントを送って記録する
+    Logic,
+    /// 表示をボードの状態に合わせる
+    Presentation,
+}
 
 pub struct BoardPlugin<T> {
     pub running_state: T,
+    /// `Some`なら、この状態の間は`BoardAssets`の読み込みを待ってから`running_state`に移る
+    pub loading_state: Option<T>,
 }
 
-impl<T: States> Plugin for BoardPlugin<T> {
+impl<T: FreelyMutableState> Plugin for BoardPlugin<T> {
     fn build(&self, app: &mut App) {
+        if let Some(loading_state) = &self.loading_state {
+            let running_state = self.running_state.clone();
+            app.add_systems(Update, board_assets_loaded
+                .pipe(move |In(loaded): In<bool>, mut next_state: ResMut<NextState<T>>| {
+                    if loaded {
+                        next_state.set(running_state.clone());
+                    }
+                })
+                .run_if(in_state(loading_state.clone())));
+        }
+
         app.add_systems(
             OnEnter(self.running_state.clone()),
-            Self::create_board,
+            (
+                Self::create_board,
+                spawn_hud,
+                start_daily_replay.run_if(resource_exists::<DailyChallenge>),
+            ).chain(),
         );
 
         app.add_systems(
             OnExit(self.running_state.clone()),
-            Self::cleanup_board,
+            (
+                Self::cleanup_board,
+                despawn_hud,
+                // 一時停止したままタイトルに戻っても時間を止めたままにしない
+                resume_time,
+                |mut next_state: ResMut<NextState<BoardState>>| next_state.set(BoardState::Inactive),
+            ),
         );
 
+        // `GamePhase`は`running_state`の間だけ存在する
+        app.init_state::<BoardState>()
+            .add_sub_state::<GamePhase>()
+            .add_systems(
+                OnEnter(self.running_state.clone()),
+                |mut next_state: ResMut<NextState<BoardState>>| next_state.set(BoardState::Active),
+            )
+            .add_systems(OnEnter(GamePhase::Paused), pause_time)
+            .add_systems(OnExit(GamePhase::Paused), resume_time);
+
+        app.configure_sets(Update, (BoardSet::Input, BoardSet::Logic, BoardSet::Presentation).chain());
+
         app.add_systems(Update, (
-            input_handling, trigger_event_handler, uncover_tiles,
-            mark_tiles,
+            (
+                input_handling.run_if(game_running),
+                apply_server_messages.run_if(resource_exists::<NetClient>),
+                // 終わった後でもやり直せるように、ゲームの進み具合には関係なく動く
+                (click_restart_button, restart_board).chain().after(input_handling),
+            ).in_set(BoardSet::Input),
+            (
+                uncover_tiles,
+                // 対戦モードのボードだけを開くので、`uncover_tiles`の後の系は対戦モードでもその後に動く
+                flags_uncover_tiles.before(uncover_tiles),
+                // 旗を立ててもクリアになる
+                record_high_score.after(uncover_tiles).after(mark_tiles).after(mark_chunk_tiles),
+                (
+                    record_daily_replay,
+                    finish_daily.after(uncover_tiles).after(mark_tiles).after(mark_chunk_tiles),
+                ).run_if(resource_exists::<DailyChallenge>),
+                mark_tiles,
+                mark_chunk_tiles.run_if(chunked_rendering),
+                advance_game_phase
+                    .after(uncover_tiles)
+                    .after(mark_tiles)
+                    .after(mark_chunk_tiles)
+                    .run_if(game_running),
+            ).in_set(BoardSet::Logic),
+            (
+                resize_board,
+                position_hud,
+                update_hud.run_if(any_changed::<Board>
+                    .or_else(resource_changed::<DailyResults>)
+                    .or_else(any_changed::<FlagsMatch>)
+                    .or_else(resource_exists_and_changed::<NetClient>)
+                    .or_else(resource_changed::<BoardSettings>)
+                    .or_else(state_changed::<GamePhase>)),
+                update_restart_button.run_if(state_changed::<GamePhase>),
+                sync_ghosts.run_if(any_changed::<Board>.or_else(resource_changed::<BoardAssets>)),
+                // 作ったばかりのボードは今の`BoardAssets`で描かれている
+                restyle_board
+                    .before(redraw_chunks)
+                    .run_if(resource_changed::<BoardAssets>.and_then(not(any_added::<Board>))),
+                (animate_covers, animate_flags, animate_debris, shake_board),
+                redraw_chunks.run_if(chunked_rendering),
+            ).in_set(BoardSet::Presentation),
         ).run_if(in_state(self.running_state.clone())));
 
+        app.add_systems(Startup, |mut images: ResMut<Assets<Image>>| insert_default_visuals(&mut images));
+
+        // テーマはどの状態でも読み込んで、ボードを作る前に`BoardAssets`を用意しておく
+        app.init_asset::<Theme>()
+            .register_asset_loader(ThemeLoader)
+            .add_systems(Update, (
+                apply_theme.run_if(resource_exists::<BoardTheme>),
+                save_settings.run_if(resource_changed::<BoardSettings>.and_then(not(resource_added::<BoardSettings>))),
+            ));
+
         app
             .add_event::<TileTriggerEvent>()
             .add_event::<BombExplosionEvent>()
             .add_event::<BoardCompletedEvent>()
-            .add_event::<TileMarkEvent>();
+            .add_event::<TileMarkEvent>()
+            .add_event::<MineClaimedEvent>()
+            .add_event::<TurnChangedEvent>()
+            .add_event::<FlagsMatchEndedEvent>()
+            .add_event::<GameStartedEvent>()
+            .add_event::<TilesRevealedEvent>()
+            .add_event::<FlagChangedEvent>()
+            .add_event::<RestartBoardEvent>()
+            .add_event::<NewBoardEvent>()
+            .add_event::<BoardAssetsFailedEvent>()
+            .add_event::<SoundCueEvent>();
+
+        if !app.world().contains_resource::<HighScores>() {
+            app.insert_resource(HighScores::load(HIGH_SCORES_PATH));
+        }
+        if !app.world().contains_resource::<BoardSettings>() {
+            app.insert_resource(BoardSettings::load(SETTINGS_PATH));
+        }
+        if !app.world().contains_resource::<DailyResults>() {
+            app.insert_resource(DailyResults::load(DAILY_RESULTS_PATH));
+        }
 
         log::info!("Loaded Board Plugin");
     }
 }
 
+/// 変更されたコンポーネント`C`があるか
+fn any_changed<C: Component>(query: Query<(), Changed<C>>) -> bool {
+    !query.is_empty()
+}
+
+/// このフレームで追加されたコンポーネント`C`があるか
+fn any_added<C: Component>(query: Query<(), Added<C>>) -> bool {
+    !query.is_empty()
+}
+
 impl<T> BoardPlugin<T> {
     /// System to generate the complete board
+    ///
+    /// デイリーチャレンジの間は`BoardOptions`のリソースを元にその日のボードにする
+    #[allow(clippy::too_many_arguments)]
     pub fn create_board(
         mut commands: Commands,
         board_options: Option<Res<BoardOptions>>,
+        daily: Option<Res<DailyChallenge>>,
         board_assets: Res<BoardAssets>,
         window: Query<&Window>,
+        mut images: ResMut<Assets<Image>>,
+        mut tile_trigger_ewr: EventWriter<TileTriggerEvent>,
+        time: Res<Time>,
     ) {
         let options = match board_options {
             None => BoardOptions::default(),
             Some(o) => o.clone(),
         };
-        let window = window.single().clone();
-
-        let mut tile_map = TileMap::empty(options.map_size.0, options.map_size.1);
-        tile_map.set_bombs(options.bomb_count);
-
-        let tile_size = match options.tile_size {
-            TileSize::Fixed(v) => { v }
-            TileSize::Adaptive { min, max } => {
-                Self::adaptative_tile_size(
-                    window,
-                    (min, max),
-                    (tile_map.width(), tile_map.height()),
-                )
-            }
+        let options = match daily {
+            Some(challenge) => challenge.board_options(&options),
+            None => options,
         };
-        let mut covered_tiles = HashMap::with_capacity((tile_map.width() * tile_map.height()) as usize);
-        let board_size = Vec2::new(
-            tile_map.width() as f32 * tile_size,
-            tile_map.height() as f32 * tile_size,
+        spawn_started_board(
+            &mut commands,
+            &options,
+            &board_assets,
+            window.single(),
+            &mut images,
+            &mut tile_trigger_ewr,
+            time.elapsed(),
+            true,
         );
-        log::info!("board size: {}", board_size);
-        let board_position = match options.position {
-            BoardPosition::Centered { offset } => {
-                Vec3::new(-(board_size.x / 2.), -(board_size.y / 2.), 0.) + offset
-            }
-            BoardPosition::Custom(p) => p
-        };
+    }
 
-        #[cfg(feature = "debug")]
-        log::info!("{}", tile_map.console_output());
+    fn cleanup_board(boards: Query<Entity, With<Board>>, mut commands: Commands) {
+        for entity in boards.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
 
-        let mut safe_start = None;
-        let board_entity = commands.spawn_empty()
-            .insert(Name::new("Board"))
-            .insert(Transform::from_translation(board_position))
-            .insert(GlobalTransform::default())
-            .insert(InheritedVisibility::VISIBLE)
-            .with_children(|parent| {
-                parent
-                    .spawn(SpriteBundle {
-                        sprite: Sprite {
-                            color: board_assets.board_material.color,
-                            custom_size: Some(board_size),
-                            ..Default::default()
-                        },
-                        transform: Transform::from_xyz(board_size.x / 2., board_size.y / 2., 0.),
-                        inherited_visibility: InheritedVisibility::VISIBLE,
+/// ボードを生成して、安全なタイルから始める、`primary`ならメインのボードにする
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn spawn_started_board(
+    commands: &mut Commands,
+    options: &BoardOptions,
+    board_assets: &BoardAssets,
+    window: &Window,
+    images: &mut Assets<Image>,
+    tile_trigger_ewr: &mut EventWriter<TileTriggerEvent>,
+    started_at: Duration,
+    primary: bool,
+) -> Entity {
+    let board = spawn_board(
+        commands,
+        options,
+        board_assets,
+        Vec2::new(window.width(), window.height()),
+        images,
+        started_at,
+    );
+    if primary {
+        commands.entity(board.entity).insert(PrimaryBoard);
+    }
+    if options.safe_start {
+        if let Some(coordinates) = board.safe_start {
+            tile_trigger_ewr.send(TileTriggerEvent { board: board.entity, coordinates, safe_start: true });
+        }
+    }
+    board.entity
+}
+
+/// `spawn_board`で生成したボード
+#[derive(Debug, Copy, Clone)]
+pub struct SpawnedBoard {
+    /// `Board`と`BoardOptions`を持つボードのエンティティ
+    pub entity: Entity,
+    /// 爆弾のない空のタイル、最初に開くと安全に始められる
+    pub safe_start: Option<Coordinates>,
+}
+
+/// `options`のボードを生成する
+///
+/// 生成したら`GameStartedEvent`を送る。ボードの状態はボードのエンティティの`Board`コンポーネントに入るので、いくつでも並べて動かせる。
+/// `BoardPlugin`は`BoardOptions`のリソースからメインのボードを1つ生成する
+pub fn spawn_board(
+    commands: &mut Commands,
+    options: &BoardOptions,
+    board_assets: &BoardAssets,
+    window_size: Vec2,
+    images: &mut Assets<Image>,
+    started_at: Duration,
+) -> SpawnedBoard {
+    let mask = options
+        .shape
+        .mask(options.map_size.0, options.map_size.1)
+        .unwrap_or_else(|e| {
+            log::error!("{}, using the full board", e);
+            None
+        });
+    let mut tile_map = TileMap::empty(options.map_size.0, options.map_size.1)
+        .with_topology(options.topology())
+        .with_mask(mask);
+    // シードがなくても`GameStartedEvent`で同じボードを作り直せるようにシードを決める
+    let seed = options.seed.unwrap_or_else(rand::random);
+    tile_map.set_bombs_seeded(options.bomb_count, options.max_bombs_per_tile, seed);
+
+    let render_mode = match (options.grid_shape, options.render_mode) {
+        (GridShape::Hexagonal, RenderMode::Chunked { .. }) => {
+            log::warn!("Chunked rendering does not support hexagonal grids, falling back to entities");
+            RenderMode::Entities
+        }
+        // 8ピクセルのタイルには2桁の数字や旗の数を描けない
+        (_, RenderMode::Chunked { .. }) if tile_map.max_bombs_per_tile() > 1 => {
+            log::warn!("Chunked rendering does not support multiple mines per tile, falling back to entities");
+            RenderMode::Entities
+        }
+        (_, render_mode) => render_mode,
+    };
+    let tile_size = options.tile_size.compute(window_size, options.board_tiles());
+    let mut tile_entities = vec![Entity::PLACEHOLDER; tile_map.len()];
+    let board_size = options.grid_shape.board_size(options.map_size, tile_size);
+    log::info!("board size: {}", board_size);
+    let board_position = options.position.translation(board_size);
+
+    #[cfg(feature = "debug")]
+    log::info!("{}", tile_map.console_output());
+
+    let mut safe_start = None;
+    let mut board_entity = commands.spawn_empty();
+    board_entity
+        .insert(Name::new("Board"))
+        .insert(Transform::from_translation(board_position))
+        .insert(GlobalTransform::default())
+        .insert(InheritedVisibility::VISIBLE)
+        .with_children(|parent| {
+            parent
+                .spawn(SpriteBundle {
+                    sprite: Sprite {
+                        color: board_assets.board_material.color,
+                        custom_size: Some(board_size),
                         ..Default::default()
-                    })
-                    .insert(Name::new("Background"));
-                Self::spawn_tiles(
+                    },
+                    transform: Transform::from_xyz(board_size.x / 2., board_size.y / 2., 0.),
+                    inherited_visibility: InheritedVisibility::VISIBLE,
+                    ..Default::default()
+                })
+                .insert(Name::new("Background"))
+                .insert(ThemePart::Background);
+            match render_mode {
+                RenderMode::Entities => spawn_tiles(
+                    parent,
+                    &tile_map,
+                    options.grid_shape,
+                    tile_size,
+                    options.tile_padding,
+                    board_assets,
+                    &mut tile_entities,
+                    &mut safe_start,
+                ),
+                RenderMode::Chunked { chunk_size } => spawn_chunks(
                     parent,
                     &tile_map,
                     tile_size,
                     options.tile_padding,
-                    &board_assets,
-                    &mut covered_tiles,
+                    chunk_size,
+                    images,
+                    &mut tile_entities,
                     &mut safe_start,
+                ),
+            }
+            if options.ghosts() {
+                spawn_ghosts(
+                    parent,
+                    &tile_map,
+                    options.grid_shape,
+                    tile_size,
+                    options.tile_padding,
+                    board_assets,
                 );
-            })
-            .id();
-        if options.safe_start {
-            if let Some(entity) = safe_start {
-                commands.entity(entity).insert(Uncover);
             }
-        }
-        commands.insert_resource(Board {
+        });
+    if let GameMode::Flags = options.mode {
+        board_entity.insert(FlagsMatch::new(tile_map.bomb_count()));
+    }
+    let entity = board_entity.id();
+    board_entity
+        .insert(BoardOptions { seed: Some(seed), ..options.clone() })
+        .insert(Board::new(
             tile_map,
-            bounds: Bounds2 {
+            Bounds2 {
                 position: board_position.xy(),
                 size: board_size,
             },
             tile_size,
-            covered_tiles,
-            entity: board_entity,
-            marked_tiles: Vec::new(),
-        });
-    }
+            entity,
+            render_mode,
+            options.grid_shape,
+            tile_entities,
+        )
+            .with_lives(options.lives)
+            .with_start_time(started_at)
+            .with_animations(options.animations));
+    let started = GameStartedEvent {
+        board: entity,
+        seed,
+        options: options.clone(),
+    };
+    commands.add(move |world: &mut World| {
+        world.send_event(started);
+    });
+    SpawnedBoard { entity, safe_start }
+}
 
-    fn adaptative_tile_size(window: Window, (min, max): (f32, f32), (width, height): (u16, u16)) -> f32 {
-        let max_width = window.width() / width as f32;
-        let max_height = window.height() / height as f32;
-        max_width.min(max_height).clamp(min, max)
-    }
+#[allow(clippy::too_many_arguments)]
+fn spawn_tiles(
+    parent: &mut ChildBuilder,
+    tile_map: &TileMap,
+    grid_shape: GridShape,
+    size: f32,
+    padding: f32,
+    board_assets: &BoardAssets,
+    tile_entities: &mut [Entity],
+    safe_start_coordinates: &mut Option<Coordinates>,
+) {
+    let tile_sprite_size = grid_shape.tile_sprite_size(size) - Vec2::splat(padding);
+    let content_size = tile_sprite_size.min_element();
+    // Tiles
+    for (coordinates, tile) in tile_map.iter() {
+        let Some(index) = tile_map.index(coordinates) else {
+            continue;
+        };
+        let (x, y) = (coordinates.x, coordinates.y);
+        let mut cmd = parent.spawn(SpriteBundle {
+            sprite: Sprite {
+                color: board_assets.tile_material.color,
+                custom_size: Some(tile_sprite_size),
+                ..Default::default()
+            },
+            transform: Transform::from_translation(
+                grid_shape.tile_center(coordinates, size).extend(1.),
+            ),
+            ..Default::default()
+        });
+        cmd.insert(Name::new(format!("Tile ({}, {})", x, y)))
+            .insert(coordinates)
+            .insert(ThemePart::Tile);
 
-    fn spawn_tiles(
-        parent: &mut ChildBuilder,
-        tile_map: &TileMap,
-        size: f32,
-        padding: f32,
-        board_assets: &BoardAssets,
-        covered_tiles: &mut HashMap<Coordinates, Entity>,
-        safe_start_entity: &mut Option<Entity>,
-    ) {
-        // Tiles
-        for (y, line) in tile_map.iter().enumerate() {
-            for (x, tile) in line.iter().enumerate() {
-                let coordinates = Coordinates {
-                    x: x as u16,
-                    y: y as u16,
-                };
-                let mut cmd = parent.spawn(SpriteBundle {
+        match tile {
+            Tile::Bomb(v) => {
+                cmd.insert(Bomb)
+                    .with_children(|parent| {
+                        parent
+                            .spawn(SpriteBundle {
+                                sprite: Sprite {
+                                    custom_size: Some(Vec2::splat(content_size)),
+                                    color: board_assets.bomb_material.color,
+                                    ..Default::default()
+                                },
+                                transform: Transform::from_xyz(0., 0., 1.),
+                                texture: board_assets.bomb_material.texture.clone(),
+                                ..Default::default()
+                            })
+                            .insert(ThemePart::Bomb);
+                        // 複数の爆弾があるタイルは爆弾の数を重ねて表示する
+                        if v > 1 {
+                            spawn_bomb_count(parent, v, board_assets, content_size, Transform::from_xyz(0., 0., 1.5));
+                        }
+                    });
+            }
+            Tile::BombNeighbor(v) => {
+                cmd.insert(BombNeighbor { count: v })
+                    .with_children(|parent| {
+                        spawn_bomb_count(parent, v, board_assets, content_size, Transform::from_xyz(0., 0., 1.));
+                    });
+            }
+            Tile::Empty => {}
+        }
+        cmd.with_children(|parent| {
+            let entity = parent
+                .spawn(SpriteBundle {
                     sprite: Sprite {
-                        color: board_assets.tile_material.color,
-                        custom_size: Some(Vec2::splat(
-                            size - padding
-                        )),
+                        custom_size: Some(tile_sprite_size),
+                        color: board_assets.covered_tile_material.color,
                         ..Default::default()
                     },
-                    transform: Transform::from_xyz(
-                        (x as f32 * size) + (size / 2.),
-                        (y as f32 * size) + (size / 2.),
-                        1.,
-                    ),
+                    transform: Transform::from_xyz(0., 0., 2.),
                     ..Default::default()
-                });
-                cmd.insert(Name::new(format!("Tile ({}, {})", x, y)))
-                    .insert(coordinates);
+                })
+                .insert(Name::new("Tile Cover"))
+                .insert(ThemePart::Cover)
+                .id();
+            tile_entities[index] = entity;
+            if safe_start_coordinates.is_none() && tile == Tile::Empty {
+                *safe_start_coordinates = Some(coordinates);
+            }
+        });
+    }
+}
 
-                match tile {
-                    Tile::Bomb => {
-                        cmd.insert(Bomb)
-                            .with_children(|parent| {
-                                parent.spawn(SpriteBundle {
-                                    sprite: Sprite {
-                                        custom_size: Some(Vec2::splat(
-                                            size - padding
-                                        )),
-                                        ..Default::default()
-                                    },
-                                    transform: Transform::from_xyz(0., 0., 1.),
-                                    texture: board_assets.bomb_material.texture.clone(),
-                                    ..Default::default()
-                                });
-                            });
-                    }
-                    Tile::BombNeighbor(v) => {
-                        cmd.insert(BombNeighbor { count: *v })
-                            .with_children(|parent| {
-                                parent.spawn(Self::bomb_count_text_bundle(
-                                    *v,
-                                    board_assets,
-                                    size - padding,
-                                ));
-                            });
-                    }
-                    Tile::Empty => {}
+/// `chunk_size`四方のタイルごとに1枚のスプライトを生成する
+#[allow(clippy::too_many_arguments)]
+fn spawn_chunks(
+    parent: &mut ChildBuilder,
+    tile_map: &TileMap,
+    size: f32,
+    padding: f32,
+    chunk_size: u16,
+    images: &mut Assets<Image>,
+    tile_entities: &mut [Entity],
+    safe_start_coordinates: &mut Option<Coordinates>,
+) {
+    let chunk_size = chunk_size.max(1);
+    for y in (0..tile_map.height()).step_by(chunk_size as usize) {
+        for x in (0..tile_map.width()).step_by(chunk_size as usize) {
+            let chunk = TileChunk {
+                origin: Coordinates { x, y },
+                width: chunk_size.min(tile_map.width() - x),
+                height: chunk_size.min(tile_map.height() - y),
+                padded: padding > 0.,
+            };
+            let mut cmd = parent.spawn_empty();
+            for coordinates in chunk.coordinates() {
+                if let Some(index) = tile_map.index(coordinates) {
+                    tile_entities[index] = cmd.id();
+                }
+                if safe_start_coordinates.is_none() && tile_map.get(coordinates) == Some(Tile::Empty) {
+                    *safe_start_coordinates = Some(coordinates);
                 }
-                cmd.with_children(|parent| {
-                    let entity = parent
-                        .spawn(SpriteBundle {
-                            sprite: Sprite {
-                                custom_size: Some(Vec2::splat(size - padding)),
-                                color: board_assets.covered_tile_material.color,
-                                ..Default::default()
-                            },
-                            transform: Transform::from_xyz(0., 0., 2.),
-                            ..Default::default()
-                        })
-                        .insert(Name::new("Tile Cover"))
-                        .id();
-                    covered_tiles.insert(coordinates, entity);
-                    if safe_start_entity.is_none() && *tile == Tile::Empty {
-                        *safe_start_entity = Some(entity);
-                    }
-                });
             }
+            // テクスチャは最初のフレームで`redraw_chunks`が描画する
+            let image = chunk_image(&chunk);
+            let chunk_world_size = Vec2::new(chunk.width as f32, chunk.height as f32) * size;
+            cmd.insert(SpriteBundle {
+                sprite: Sprite {
+                    custom_size: Some(chunk_world_size),
+                    ..Default::default()
+                },
+                texture: images.add(image),
+                transform: Transform::from_xyz(
+                    (x as f32 * size) + (chunk_world_size.x / 2.),
+                    (y as f32 * size) + (chunk_world_size.y / 2.),
+                    1.,
+                ),
+                ..Default::default()
+            })
+                .insert(Name::new(format!("Chunk ({}, {})", x, y)))
+                .insert(chunk)
+                .insert(ChunkRedraw);
         }
     }
+}
 
-    fn bomb_count_text_bundle(
-        count: u8,
-        board_assets: &BoardAssets,
-        size: f32,
-    ) -> Text2dBundle {
-        let color = board_assets.bomb_counter_color(count);
-        Text2dBundle {
-            text: Text {
-                sections: vec![TextSection {
-                    value: count.to_string(),
-                    style: TextStyle {
-                        color,
-                        font: board_assets.bomb_counter_font.clone(),
-                        font_size: size,
-                    },
-                }],
-                justify: JustifyText::Center,
-                linebreak_behavior: Default::default(),
-            },
-            transform: Transform::from_xyz(0., 0., 1.),
-            ..Default::default()
-        }
+/// 端が繋がったボードの外側に、反対側の列と行の複製を生成する
+fn spawn_ghosts(
+    parent: &mut ChildBuilder,
+    tile_map: &TileMap,
+    grid_shape: GridShape,
+    size: f32,
+    padding: f32,
+    board_assets: &BoardAssets,
+) {
+    let (width, height) = (tile_map.width(), tile_map.height());
+    if width == 0 || height == 0 {
+        return;
+    }
+    let (right, top) = (width - 1, height - 1);
+    let mut ghosts = Vec::with_capacity(2 * (width + height) as usize + 4);
+    for y in 0..height {
+        ghosts.push((Coordinates { x: right, y }, (-1, 0)));
+        ghosts.push((Coordinates { x: 0, y }, (1, 0)));
+    }
+    for x in 0..width {
+        ghosts.push((Coordinates { x, y: top }, (0, -1)));
+        ghosts.push((Coordinates { x, y: 0 }, (0, 1)));
     }
+    ghosts.extend([
+        (Coordinates { x: right, y: top }, (-1, -1)),
+        (Coordinates { x: 0, y: top }, (1, -1)),
+        (Coordinates { x: right, y: 0 }, (-1, 1)),
+        (Coordinates { x: 0, y: 0 }, (1, 1)),
+    ]);
 
-    fn cleanup_board(board: Res<Board>, mut commands: Commands) {
-        commands.entity(board.entity).despawn_recursive();
-        commands.remove_resource::<Board>();
+    let tile_sprite_size = grid_shape.tile_sprite_size(size) - Vec2::splat(padding);
+    let content_size = tile_sprite_size.min_element();
+    for (source, offset) in ghosts {
+        if !tile_map.exists(source) {
+            continue;
+        }
+        let mut content = None;
+        let mut flag = Entity::PLACEHOLDER;
+        let center = grid_shape.wrapped_tile_center(source, offset, (width, height), size);
+        parent
+            .spawn(SpriteBundle {
+                sprite: Sprite {
+                    color: board_assets.covered_tile_material.color.with_alpha(GHOST_ALPHA),
+                    custom_size: Some(tile_sprite_size),
+                    ..Default::default()
+                },
+                transform: Transform::from_translation(center.extend(1.)),
+                ..Default::default()
+            })
+            .with_children(|parent| {
+                content = match tile_map.get(source) {
+                    Some(Tile::Bomb(_)) => Some(
+                        parent
+                            .spawn(SpriteBundle {
+                                sprite: Sprite {
+                                    custom_size: Some(Vec2::splat(content_size)),
+                                    color: board_assets.bomb_material.color.with_alpha(GHOST_ALPHA),
+                                    ..Default::default()
+                                },
+                                transform: Transform::from_xyz(0., 0., 1.),
+                                texture: board_assets.bomb_material.texture.clone(),
+                                visibility: Visibility::Hidden,
+                                ..Default::default()
+                            })
+                            .insert(ThemePart::Bomb)
+                            .id(),
+                    ),
+                    Some(Tile::BombNeighbor(v)) => {
+                        Some(
+                            spawn_bomb_count(parent, v, board_assets, content_size, Transform::from_xyz(0., 0., 1.))
+                                .insert(Visibility::Hidden)
+                                .id(),
+                        )
+                    }
+                    _ => None,
+                };
+                flag = parent
+                    .spawn(SpriteBundle {
+                        sprite: Sprite {
+                            custom_size: Some(Vec2::splat(content_size)),
+                            color: board_assets.flag_material.color.with_alpha(GHOST_ALPHA),
+                            ..Default::default()
+                        },
+                        transform: Transform::from_xyz(0., 0., 2.),
+                        texture: board_assets.flag_material.texture.clone(),
+                        visibility: Visibility::Hidden,
+                        ..Default::default()
+                    })
+                    .insert(ThemePart::Flag)
+                    .id();
+            })
+            .insert(Name::new(format!("Ghost ({}, {})", source.x, source.y)))
+            .insert(Ghost {
+                source,
+                offset,
+                content,
+                flag,
+            });
     }
-}
\ No newline at end of file
+}
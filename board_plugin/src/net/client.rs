@@ -0,0 +1,80 @@
+use std::io::{BufRead, BufReader};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::mpsc::{channel, Receiver};
+use std::sync::Mutex;
+use std::thread;
+use bevy::prelude::Resource;
+use crate::net::protocol::{send_line, ClientMessage, NetMode, ServerMessage, Welcome};
+
+/// サーバーとの接続、リソースとして存在する間は操作をサーバーに送る
+#[derive(Debug, Resource)]
+pub struct NetClient {
+    pub player: u8,
+    pub mode: NetMode,
+    /// ゲームが終わっていれば勝者
+    pub finished: Option<Option<u8>>,
+    stream: TcpStream,
+    receiver: Mutex<Receiver<ServerMessage>>,
+}
+
+impl NetClient {
+    /// サーバーに接続して、ボードの情報を受け取るまで待つ
+    pub fn connect(addr: impl ToSocketAddrs, name: &str) -> std::io::Result<(Self, Welcome)> {
+        let mut stream = TcpStream::connect(addr)?;
+        send_line(&mut stream, &ClientMessage::Hello { name: name.to_string() })?;
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        let welcome = match serde_json::from_str(&line)? {
+            ServerMessage::Welcome(welcome) => welcome,
+            message => {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!("expected Welcome, got {:?}", message),
+                ))
+            }
+        };
+
+        let (tx, rx) = channel();
+        thread::spawn(move || {
+            for line in reader.lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                match serde_json::from_str(&line) {
+                    Ok(message) => {
+                        if tx.send(message).is_err() {
+                            return;
+                        }
+                    }
+                    Err(e) => log::warn!("Invalid message from server: {}", e),
+                }
+            }
+            log::warn!("Disconnected from server");
+        });
+        Ok((
+            Self {
+                player: welcome.player,
+                mode: welcome.mode,
+                finished: None,
+                stream,
+                receiver: Mutex::new(rx),
+            },
+            welcome,
+        ))
+    }
+
+    pub fn send(&mut self, message: &ClientMessage) {
+        if let Err(e) = send_line(&mut self.stream, message) {
+            log::error!("Failed to send {:?}: {}", message, e);
+        }
+    }
+
+    /// 受信済みのメッセージを全て取り出す
+    pub fn poll(&self) -> Vec<ServerMessage> {
+        match self.receiver.lock() {
+            Ok(receiver) => receiver.try_iter().collect(),
+            Err(_) => Vec::new(),
+        }
+    }
+}
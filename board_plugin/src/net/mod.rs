@@ -0,0 +1,10 @@
+//! ローカルのTCPで遊ぶ対戦と協力プレイ
+//!
+//! サーバーが`TileMap`を持ち、クライアントの操作を全て検証してから結果を配る。
+//! メッセージの形式は[`protocol`]を参照
+pub mod client;
+pub mod protocol;
+pub mod server;
+
+/// サーバーが待ち受ける既定のポート
+pub const DEFAULT_PORT: u16 = 7878;
@@ -0,0 +1,93 @@
+//! サーバーとクライアントの間のメッセージ
+//!
+//! 1行に1つのメッセージをJSONで送る。列挙型はserdeの既定の外部タグ形式になる
+//!
+//! ```text
+//! -> {"Hello":{"name":"alice"}}
+//! <- {"Welcome":{"player":0,"mode":"Coop","map_size":[16,16],"bomb_count":40,"seed":42}}
+//! -> {"Uncover":{"x":3,"y":4}}
+//! <- {"Uncovered":{"player":0,"coordinates":{"x":3,"y":4}}}
+//! -> {"Mark":{"x":5,"y":1}}
+//! <- {"Marked":{"player":0,"coordinates":{"x":5,"y":1}}}
+//! <- {"Finished":{"winner":0}}
+//! ```
+//!
+//! 協力プレイでは全員の操作が全員に配られ、途中から参加したクライアントには
+//! それまでの操作が`Welcome`の後に続けて送られる。対戦ではそれぞれが同じシードの
+//! 別のボードを遊び、自分の操作だけが返ってくる
+use std::io::Write;
+use std::net::TcpStream;
+use serde::{Deserialize, Serialize};
+use crate::components::Coordinates;
+use crate::resources::board_options::{BoardOptions, GameMode};
+
+/// 複数人で遊ぶ方法
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum NetMode {
+    /// 同じシードの別々のボードで、最初に全て開いたプレイヤーが勝つ
+    Versus,
+    /// 1つのボードを全員で開く
+    Coop,
+}
+
+/// クライアントからサーバーへの操作の要求
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum ClientMessage {
+    /// 接続して最初に送る
+    Hello { name: String },
+    /// タイルを開く、開いている数字のタイルならコード
+    Uncover(Coordinates),
+    /// 旗を切り替える
+    Mark(Coordinates),
+}
+
+/// 接続したクライアントに送るボードの情報
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Welcome {
+    pub player: u8,
+    pub mode: NetMode,
+    pub map_size: (u16, u16),
+    pub bomb_count: u32,
+    pub seed: u64,
+}
+
+impl Welcome {
+    /// `base`の見た目の設定を残して、サーバーと同じボードになるオプションにする
+    pub fn board_options(&self, base: &BoardOptions) -> BoardOptions {
+        BoardOptions {
+            map_size: self.map_size,
+            bomb_count: self.bomb_count,
+            max_bombs_per_tile: 1,
+            safe_start: false,
+            lives: 1,
+            seed: Some(self.seed),
+            mode: GameMode::Classic,
+            neighborhood: Default::default(),
+            grid_shape: Default::default(),
+            edges: Default::default(),
+            shape: Default::default(),
+            ..base.clone()
+        }
+    }
+}
+
+/// サーバーからクライアントへの通知
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ServerMessage {
+    Welcome(Welcome),
+    /// サーバーが認めた開く操作、クライアントは同じタイルを開く
+    Uncovered { player: u8, coordinates: Coordinates },
+    /// サーバーが認めた旗の操作
+    Marked { player: u8, coordinates: Coordinates },
+    /// ゲームが終わった、全員が負けたら`winner`は`None`
+    Finished { winner: Option<u8> },
+    /// 受け付けなかった要求
+    Rejected { reason: String },
+}
+
+/// 1行のJSONとして送る
+pub(crate) fn send_line<T: Serialize>(stream: &mut TcpStream, message: &T) -> std::io::Result<()> {
+    let mut line = serde_json::to_string(message)?;
+    line.push('\n');
+    stream.write_all(line.as_bytes())
+}
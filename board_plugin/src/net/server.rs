@@ -0,0 +1,456 @@
+use std::io::{BufRead, BufReader};
+use std::net::{Shutdown, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::mpsc::{channel, Sender};
+use std::thread;
+use bevy::prelude::{Entity, Vec2};
+use bevy::utils::HashMap;
+use crate::bounds::Bounds2;
+use crate::net::protocol::{send_line, ClientMessage, NetMode, ServerMessage, Welcome};
+use crate::resources::board::Board;
+use crate::resources::board_options::RenderMode;
+use crate::resources::grid_shape::GridShape;
+use crate::resources::tile_map::TileMap;
+
+/// サーバーで遊ぶボード
+#[derive(Debug, Copy, Clone)]
+pub struct ServerConfig {
+    pub mode: NetMode,
+    pub map_size: (u16, u16),
+    pub bomb_count: u32,
+    pub seed: u64,
+}
+
+/// 接続ごとのスレッドからサーバーのループへの通知、接続は受け付けた順の番号で区別する
+enum Connection {
+    Opened(u64, TcpStream),
+    Message(u64, ClientMessage),
+    Closed(u64),
+}
+
+/// `addr`で待ち受けて、ボードの状態を管理し続ける
+pub fn run(addr: impl ToSocketAddrs, config: ServerConfig) -> std::io::Result<()> {
+    serve(TcpListener::bind(addr)?, config)
+}
+
+/// `listener`で受け付けた接続でボードの状態を管理し続ける
+///
+/// 接続ごとにスレッドで受信し、ボードの操作は1つのループで順に処理する
+pub fn serve(listener: TcpListener, config: ServerConfig) -> std::io::Result<()> {
+    log::info!("Listening on {} ({:?})", listener.local_addr()?, config.mode);
+    let (tx, rx) = channel();
+    thread::spawn(move || accept(listener, tx));
+
+    let mut server = Server::new(config);
+    for connection in rx {
+        match connection {
+            Connection::Opened(id, stream) => server.open(id, stream),
+            Connection::Message(id, message) => server.handle(id, message),
+            Connection::Closed(id) => server.close(id),
+        }
+    }
+    Ok(())
+}
+
+fn accept(listener: TcpListener, tx: Sender<Connection>) {
+    for (id, stream) in (0_u64..).zip(listener.incoming()) {
+        let stream = match stream {
+            Ok(s) => s,
+            Err(e) => {
+                log::error!("Failed to accept connection: {}", e);
+                continue;
+            }
+        };
+        let reader = match stream.try_clone() {
+            Ok(s) => s,
+            Err(e) => {
+                log::error!("Failed to clone connection: {}", e);
+                continue;
+            }
+        };
+        if tx.send(Connection::Opened(id, stream)).is_err() {
+            return;
+        }
+        let tx = tx.clone();
+        thread::spawn(move || {
+            for line in BufReader::new(reader).lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                match serde_json::from_str(&line) {
+                    Ok(message) => {
+                        if tx.send(Connection::Message(id, message)).is_err() {
+                            return;
+                        }
+                    }
+                    Err(e) => log::warn!("Invalid message from connection {}: {}", id, e),
+                }
+            }
+            let _ = tx.send(Connection::Closed(id));
+        });
+    }
+}
+
+struct Server {
+    config: ServerConfig,
+    /// 接続の番号ごとのプレイヤー
+    players: HashMap<u64, u8>,
+    clients: HashMap<u8, TcpStream>,
+    /// 対戦ではプレイヤーごと、協力プレイでは`0`だけ
+    boards: HashMap<u8, Board>,
+    /// 協力プレイで途中から参加したクライアントに送る、それまでの操作
+    history: Vec<ServerMessage>,
+    finished: bool,
+}
+
+impl Server {
+    fn new(config: ServerConfig) -> Self {
+        Self {
+            config,
+            players: HashMap::new(),
+            clients: HashMap::new(),
+            boards: HashMap::new(),
+            history: Vec::new(),
+            finished: false,
+        }
+    }
+
+    /// サーバーの持つボード、描画しないのでエンティティは使わない
+    fn new_board(&self) -> Board {
+        let (width, height) = self.config.map_size;
+        let mut tile_map = TileMap::empty(width, height);
+        tile_map.set_bombs_seeded(self.config.bomb_count, 1, self.config.seed);
+        let tile_entities = vec![Entity::PLACEHOLDER; tile_map.len()];
+        Board::new(
+            tile_map,
+            Bounds2 {
+                position: Vec2::ZERO,
+                size: Vec2::ZERO,
+            },
+            1.,
+            Entity::PLACEHOLDER,
+            RenderMode::Entities,
+            GridShape::Square,
+            tile_entities,
+        )
+    }
+
+    fn board_key(&self, player: u8) -> u8 {
+        match self.config.mode {
+            NetMode::Versus => player,
+            NetMode::Coop => 0,
+        }
+    }
+
+    /// 空いている一番小さいプレイヤーの番号を割り当てる、切断したプレイヤーの番号は使い回す
+    ///
+    /// 番号が全て使われていれば断って接続を閉じ、次の接続は受け付け続ける
+    fn open(&mut self, id: u64, mut stream: TcpStream) {
+        let Some(player) = (0..=u8::MAX).find(|p| !self.clients.contains_key(p)) else {
+            log::warn!("Rejected connection {}, server is full", id);
+            let _ = send_line(&mut stream, &ServerMessage::Rejected { reason: "server is full".to_string() });
+            let _ = stream.shutdown(Shutdown::Both);
+            return;
+        };
+        log::info!("Player {} connected", player);
+        let welcome = ServerMessage::Welcome(Welcome {
+            player,
+            mode: self.config.mode,
+            map_size: self.config.map_size,
+            bomb_count: self.config.bomb_count,
+            seed: self.config.seed,
+        });
+        let history = match self.config.mode {
+            NetMode::Versus => &[][..],
+            NetMode::Coop => &self.history[..],
+        };
+        let sent = std::iter::once(&welcome)
+            .chain(history)
+            .try_for_each(|m| send_line(&mut stream, m));
+        if let Err(e) = sent {
+            log::error!("Failed to welcome player {}: {}", player, e);
+            return;
+        }
+        let key = self.board_key(player);
+        // 対戦では番号を使い回したプレイヤーも新しいボードで始める
+        if self.config.mode == NetMode::Versus || !self.boards.contains_key(&key) {
+            let board = self.new_board();
+            self.boards.insert(key, board);
+        }
+        self.players.insert(id, player);
+        self.clients.insert(player, stream);
+    }
+
+    fn close(&mut self, id: u64) {
+        // 断った接続はプレイヤーになっていない
+        let Some(player) = self.players.remove(&id) else {
+            return;
+        };
+        log::info!("Player {} disconnected", player);
+        self.clients.remove(&player);
+        if self.config.mode == NetMode::Versus {
+            self.boards.remove(&player);
+        }
+    }
+
+    fn handle(&mut self, id: u64, message: ClientMessage) {
+        let Some(&player) = self.players.get(&id) else {
+            return;
+        };
+        if self.finished {
+            self.reply(player, ServerMessage::Rejected { reason: "game is over".to_string() });
+            return;
+        }
+        let key = self.board_key(player);
+        let Some(board) = self.boards.get_mut(&key) else {
+            return;
+        };
+        match message {
+            ClientMessage::Hello { name } => log::info!("Player {} is {}", player, name),
+            ClientMessage::Uncover(coordinates) => {
+                if board.is_lost() {
+                    self.reply(player, ServerMessage::Rejected { reason: "no lives left".to_string() });
+                    return;
+                }
+                let targets = board.trigger_targets(coordinates);
+                let mut uncovered = false;
+                for target in targets {
+                    uncovered |= !board.uncover_region(target).is_empty();
+                    if board.tile_map.is_bomb_at(target) && board.detonate(&target) == 0 {
+                        break;
+                    }
+                }
+                if !uncovered {
+                    self.reply(player, ServerMessage::Rejected { reason: format!("nothing to uncover on {}", coordinates) });
+                    return;
+                }
+                self.publish(player, ServerMessage::Uncovered { player, coordinates });
+                self.check_finished(player);
+            }
+            ClientMessage::Mark(coordinates) => {
+                if board.try_cycle_mark(&coordinates).is_none() {
+                    self.reply(player, ServerMessage::Rejected { reason: format!("cannot mark {}", coordinates) });
+                    return;
+                }
+                self.publish(player, ServerMessage::Marked { player, coordinates });
+                // 旗が全ての爆弾と合えば旗でも勝つ
+                self.check_finished(player);
+            }
+        }
+    }
+
+    /// 勝ち負けが決まったら全員に知らせる
+    fn check_finished(&mut self, player: u8) {
+        let Some(board) = self.boards.get(&self.board_key(player)) else {
+            return;
+        };
+        // 協力プレイでは最後に開いたプレイヤーを勝者として送る
+        let winner = if board.is_completed() {
+            Some(player)
+        } else if self.boards.values().all(Board::is_lost) {
+            None
+        } else {
+            return;
+        };
+        self.finished = true;
+        log::info!("Game finished, winner: {:?}", winner);
+        self.broadcast(ServerMessage::Finished { winner });
+    }
+
+    /// 協力プレイでは全員に、対戦では操作したプレイヤーにだけ送る
+    fn publish(&mut self, player: u8, message: ServerMessage) {
+        match self.config.mode {
+            NetMode::Versus => self.reply(player, message),
+            NetMode::Coop => {
+                self.history.push(message.clone());
+                self.broadcast(message);
+            }
+        }
+    }
+
+    fn reply(&mut self, player: u8, message: ServerMessage) {
+        if let Some(stream) = self.clients.get_mut(&player) {
+            if let Err(e) = send_line(stream, &message) {
+                log::error!("Failed to send to player {}: {}", player, e);
+            }
+        }
+    }
+
+    fn broadcast(&mut self, message: ServerMessage) {
+        for (player, stream) in self.clients.iter_mut() {
+            if let Err(e) = send_line(stream, &message) {
+                log::error!("Failed to send to player {}: {}", player, e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::SocketAddr;
+    use std::time::Duration;
+    use crate::components::Coordinates;
+    use crate::resources::tile::Tile;
+    use super::*;
+
+    fn config(mode: NetMode) -> ServerConfig {
+        ServerConfig {
+            mode,
+            map_size: (8, 8),
+            bomb_count: 10,
+            seed: 42,
+        }
+    }
+
+    /// 空いているポートでサーバーを動かす
+    fn start(mode: NetMode) -> SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        thread::spawn(move || serve(listener, config(mode)));
+        addr
+    }
+
+    /// 1行ずつ読み書きするクライアント
+    struct TestClient {
+        stream: TcpStream,
+        reader: BufReader<TcpStream>,
+    }
+
+    impl TestClient {
+        fn connect(addr: SocketAddr, name: &str) -> Self {
+            let stream = TcpStream::connect(addr).unwrap();
+            stream.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+            let reader = BufReader::new(stream.try_clone().unwrap());
+            let mut client = Self { stream, reader };
+            client.send(&ClientMessage::Hello { name: name.to_string() });
+            client
+        }
+
+        fn send(&mut self, message: &ClientMessage) {
+            send_line(&mut self.stream, message).unwrap();
+        }
+
+        fn recv(&mut self) -> ServerMessage {
+            let mut line = String::new();
+            self.reader.read_line(&mut line).unwrap();
+            serde_json::from_str(&line).unwrap()
+        }
+
+        fn welcome(&mut self) -> Welcome {
+            match self.recv() {
+                ServerMessage::Welcome(welcome) => welcome,
+                message => panic!("expected Welcome, got {:?}", message),
+            }
+        }
+    }
+
+    /// サーバーと同じシードのマップで条件に合う最初のタイル
+    fn find_tile(mode: NetMode, matches: impl Fn(Tile) -> bool) -> Coordinates {
+        let config = config(mode);
+        let mut tile_map = TileMap::empty(config.map_size.0, config.map_size.1);
+        tile_map.set_bombs_seeded(config.bomb_count, 1, config.seed);
+        let found = tile_map.iter().find(|(_, tile)| matches(*tile));
+        found.map(|(c, _)| c).unwrap()
+    }
+
+    #[test]
+    fn welcome_describes_the_board() {
+        let addr = start(NetMode::Versus);
+        let mut alice = TestClient::connect(addr, "alice");
+        let mut bob = TestClient::connect(addr, "bob");
+        let welcome = alice.welcome();
+        assert_eq!(
+            welcome,
+            Welcome { player: 0, mode: NetMode::Versus, map_size: (8, 8), bomb_count: 10, seed: 42 }
+        );
+        assert_eq!(bob.welcome().player, 1);
+    }
+
+    #[test]
+    fn coop_actions_are_broadcast() {
+        let addr = start(NetMode::Coop);
+        let mut alice = TestClient::connect(addr, "alice");
+        alice.welcome();
+        let mut bob = TestClient::connect(addr, "bob");
+        bob.welcome();
+
+        let coordinates = find_tile(NetMode::Coop, |t| matches!(t, Tile::BombNeighbor(_)));
+        alice.send(&ClientMessage::Uncover(coordinates));
+        let uncovered = ServerMessage::Uncovered { player: 0, coordinates };
+        assert_eq!(alice.recv(), uncovered);
+        assert_eq!(bob.recv(), uncovered);
+
+        let coordinates = find_tile(NetMode::Coop, |t| t.is_bomb());
+        bob.send(&ClientMessage::Mark(coordinates));
+        let marked = ServerMessage::Marked { player: 1, coordinates };
+        assert_eq!(alice.recv(), marked);
+        assert_eq!(bob.recv(), marked);
+    }
+
+    #[test]
+    fn coop_late_joiner_receives_history() {
+        let addr = start(NetMode::Coop);
+        let mut alice = TestClient::connect(addr, "alice");
+        alice.welcome();
+        let coordinates = find_tile(NetMode::Coop, |t| matches!(t, Tile::BombNeighbor(_)));
+        alice.send(&ClientMessage::Uncover(coordinates));
+        alice.recv();
+
+        let mut bob = TestClient::connect(addr, "bob");
+        assert_eq!(bob.welcome().player, 1);
+        assert_eq!(bob.recv(), ServerMessage::Uncovered { player: 0, coordinates });
+    }
+
+    #[test]
+    fn invalid_action_is_rejected() {
+        let addr = start(NetMode::Versus);
+        let mut alice = TestClient::connect(addr, "alice");
+        alice.welcome();
+        let coordinates = find_tile(NetMode::Versus, |t| matches!(t, Tile::BombNeighbor(_)));
+        alice.send(&ClientMessage::Uncover(coordinates));
+        assert_eq!(alice.recv(), ServerMessage::Uncovered { player: 0, coordinates });
+        // 開いたタイルには旗を立てられない
+        alice.send(&ClientMessage::Mark(coordinates));
+        assert!(matches!(alice.recv(), ServerMessage::Rejected { .. }));
+    }
+
+    #[test]
+    fn freed_player_numbers_are_reused() {
+        let addr = start(NetMode::Versus);
+        let mut alice = TestClient::connect(addr, "alice");
+        assert_eq!(alice.welcome().player, 0);
+        let mut bob = TestClient::connect(addr, "bob");
+        assert_eq!(bob.welcome().player, 1);
+        alice.stream.shutdown(Shutdown::Both).unwrap();
+        drop(alice);
+
+        // 切断がサーバーに届くまでは次の番号になるので、繋ぎ直して待つ
+        let reused = (0..50).any(|_| {
+            let mut carol = TestClient::connect(addr, "carol");
+            let player = carol.welcome().player;
+            if player != 0 {
+                thread::sleep(Duration::from_millis(20));
+            }
+            player == 0
+        });
+        assert!(reused);
+    }
+
+    #[test]
+    fn flagging_every_mine_finishes_the_game() {
+        let addr = start(NetMode::Versus);
+        let mut alice = TestClient::connect(addr, "alice");
+        alice.welcome();
+        let config = config(NetMode::Versus);
+        let mut tile_map = TileMap::empty(config.map_size.0, config.map_size.1);
+        tile_map.set_bombs_seeded(config.bomb_count, 1, config.seed);
+        let mines: Vec<_> = tile_map.iter().filter(|(_, t)| t.is_bomb()).map(|(c, _)| c).collect();
+        for coordinates in mines {
+            alice.send(&ClientMessage::Mark(coordinates));
+            assert_eq!(alice.recv(), ServerMessage::Marked { player: 0, coordinates });
+        }
+        assert_eq!(alice.recv(), ServerMessage::Finished { winner: Some(0) });
+        let coordinates = find_tile(NetMode::Versus, |t| !t.is_bomb());
+        alice.send(&ClientMessage::Uncover(coordinates));
+        assert!(matches!(alice.recv(), ServerMessage::Rejected { .. }));
+    }
+}
@@ -1,20 +1,107 @@
-use bevy::prelude::{Entity, Resource, Vec2, Window};
+use std::collections::VecDeque;
+use std::time::Duration;
+use bevy::prelude::{Component, Entity, Vec2, Window};
 use bevy::utils::HashMap;
+use fixedbitset::FixedBitSet;
 use crate::bounds::Bounds2;
 use crate::components::Coordinates;
+use crate::resources::board_options::RenderMode;
+use crate::resources::grid_shape::GridShape;
+use crate::resources::tile::Tile;
 use crate::resources::tile_map::TileMap;
 
-#[derive(Debug, Resource)]
+/// ボードの状態、ボードのエンティティのコンポーネント
+///
+/// 1つのワールドにいくつでも置けて、システムは全てのボードを順に更新する
+#[derive(Debug, Component)]
 pub struct Board {
     pub tile_map: TileMap,
     pub bounds: Bounds2,
     pub tile_size: f32,
-    pub covered_tiles: HashMap<Coordinates, Entity>,
     pub entity: Entity,
-    pub marked_tiles: Vec<Coordinates>,
+    pub render_mode: RenderMode,
+    pub grid_shape: GridShape,
+    /// アニメーションを再生するか
+    pub animations: bool,
+    /// タイルごとのカバー、チャンク描画ではチャンクのエンティティ
+    tile_entities: Vec<Entity>,
+    /// カバーされているタイル
+    covered: FixedBitSet,
+    /// マーキングされているタイル
+    marked: FixedBitSet,
+    /// マーキングされているタイルの旗の数
+    flag_levels: HashMap<usize, u8>,
+    /// 開いて自動で旗が立った爆弾のタイル
+    revealed_mines: FixedBitSet,
+    /// 対戦モードで爆弾を見つけたプレイヤー
+    mine_owners: HashMap<usize, u8>,
+    covered_count: usize,
+    lives: u8,
+    max_lives: u8,
+    /// ボードを生成した時刻、`Time::elapsed`
+    started_at: Duration,
 }
 
 impl Board {
+    /// 存在する全てのタイルがカバーされたボードを作る
+    ///
+    /// `tile_entities`は`tile_map`と同じ行優先の順に並べる
+    pub fn new(
+        tile_map: TileMap,
+        bounds: Bounds2,
+        tile_size: f32,
+        entity: Entity,
+        render_mode: RenderMode,
+        grid_shape: GridShape,
+        tile_entities: Vec<Entity>,
+    ) -> Self {
+        debug_assert_eq!(tile_map.len(), tile_entities.len());
+        let mut covered = FixedBitSet::with_capacity(tile_map.len());
+        for (coords, _) in tile_map.iter() {
+            if let Some(index) = tile_map.index(coords) {
+                covered.insert(index);
+            }
+        }
+        Self {
+            marked: FixedBitSet::with_capacity(tile_map.len()),
+            flag_levels: HashMap::new(),
+            revealed_mines: FixedBitSet::with_capacity(tile_map.len()),
+            mine_owners: HashMap::new(),
+            covered_count: tile_map.tile_count(),
+            lives: 1,
+            max_lives: 1,
+            started_at: Duration::ZERO,
+            animations: false,
+            tile_map,
+            bounds,
+            tile_size,
+            entity,
+            render_mode,
+            grid_shape,
+            tile_entities,
+            covered,
+        }
+    }
+
+    /// 爆発に耐えられる回数を設定する、最後のライフで爆発するとゲームオーバー
+    pub fn with_lives(mut self, lives: u8) -> Self {
+        self.max_lives = lives.max(1);
+        self.lives = self.max_lives;
+        self
+    }
+
+    /// アニメーションを再生するかを設定する
+    pub fn with_animations(mut self, animations: bool) -> Self {
+        self.animations = animations;
+        self
+    }
+
+    /// ボードを生成した時刻を設定する
+    pub fn with_start_time(mut self, started_at: Duration) -> Self {
+        self.started_at = started_at;
+        self
+    }
+
     pub fn mouse_position(&self, window: &Window, position: Vec2) -> Option<Coordinates> {
         let window_size = Vec2::new(window.width(), window.height());
         let position = position - window_size / 2.;
@@ -23,64 +110,463 @@ impl Board {
         if !self.bounds.is_bounds(position) {
             return None;
         }
-        let coordinates = position - self.bounds.position;
-        Some(Coordinates {
-            x: (coordinates.x / self.tile_size) as u16,
-            y: (coordinates.y / self.tile_size) as u16,
-        })
+        self.grid_shape.tile_at(
+            position - self.bounds.position,
+            self.tile_size,
+            (self.tile_map.width(), self.tile_map.height()),
+        )
+    }
+
+    pub fn is_covered(&self, coords: &Coordinates) -> bool {
+        self.tile_map
+            .index(*coords)
+            .is_some_and(|i| self.covered.contains(i))
+    }
+
+    pub fn is_marked(&self, coords: &Coordinates) -> bool {
+        self.tile_map
+            .index(*coords)
+            .is_some_and(|i| self.marked.contains(i))
+    }
+
+    /// タイルに立っている旗の数、開いた爆弾のタイルは爆弾の数だけ旗が立っている
+    pub fn flag_level(&self, coords: &Coordinates) -> u8 {
+        let Some(index) = self.tile_map.index(*coords) else {
+            return 0;
+        };
+        if self.revealed_mines.contains(index) {
+            return self.tile_map.bombs_at(*coords);
+        }
+        self.flag_levels.get(&index).copied().unwrap_or(0)
+    }
+
+    /// ボード全体の旗の合計の数
+    pub fn flag_total(&self) -> usize {
+        let revealed: usize = self
+            .revealed_mines
+            .ones()
+            .map(|i| self.tile_map.bombs_at(self.tile_map.coordinates(i)) as usize)
+            .sum();
+        self.flag_levels.values().map(|v| *v as usize).sum::<usize>() + revealed
+    }
+
+    /// 残りのライフ
+    pub fn lives(&self) -> u8 {
+        self.lives
+    }
+
+    /// 最初のライフ
+    pub fn max_lives(&self) -> u8 {
+        self.max_lives
+    }
+
+    /// 失ったライフの数
+    pub fn lives_lost(&self) -> u8 {
+        self.max_lives - self.lives
+    }
+
+    /// ライフを全て失ったか
+    pub fn is_lost(&self) -> bool {
+        self.lives == 0
+    }
+
+    /// ボードを生成した時刻
+    pub fn started_at(&self) -> Duration {
+        self.started_at
+    }
+
+    /// 開いた爆弾のタイルでライフを1つ失い、タイルに自動で旗を立てる
+    ///
+    /// 残りのライフを返す
+    pub fn detonate(&mut self, coords: &Coordinates) -> u8 {
+        if self.reveal_mine(coords, None) {
+            self.lives = self.lives.saturating_sub(1);
+        }
+        self.lives
+    }
+
+    /// 開いた爆弾のタイルに自動で旗を立てる、対戦モードでは見つけたプレイヤーを記録する
+    ///
+    /// 新しく旗を立てたら`true`
+    pub fn reveal_mine(&mut self, coords: &Coordinates, owner: Option<u8>) -> bool {
+        let Some(index) = self.tile_map.index(*coords) else {
+            return false;
+        };
+        if self.revealed_mines.contains(index) {
+            return false;
+        }
+        self.revealed_mines.insert(index);
+        if let Some(owner) = owner {
+            self.mine_owners.insert(index, owner);
+        }
+        true
+    }
+
+    /// 爆弾を見つけたプレイヤー
+    pub fn mine_owner(&self, coords: &Coordinates) -> Option<u8> {
+        self.tile_map
+            .index(*coords)
+            .and_then(|i| self.mine_owners.get(&i))
+            .copied()
+    }
+
+    /// カバーされているタイルの数
+    pub fn covered_count(&self) -> usize {
+        self.covered_count
+    }
+
+    /// マーキングされているタイルの座標
+    pub fn marked_tiles(&self) -> impl Iterator<Item = Coordinates> + '_ {
+        self.marked.ones().map(|i| self.tile_map.coordinates(i))
+    }
+
+    /// カバーされたタイルのエンティティを取得する
+    pub fn covered_entity(&self, coords: &Coordinates) -> Option<Entity> {
+        let index = self.tile_map.index(*coords)?;
+        self.covered.contains(index).then(|| self.tile_entities[index])
     }
 
     /// カバーされたタイルエンティティを取得する
-    pub fn tile_to_uncover(&self, coords: &Coordinates) -> Option<&Entity> {
-        if self.marked_tiles.contains(coords) {
+    pub fn tile_to_uncover(&self, coords: &Coordinates) -> Option<Entity> {
+        if self.is_marked(coords) {
             None
         } else {
-            self.covered_tiles.get(coords)
+            self.covered_entity(coords)
         }
     }
     /// タイルのカバーを取ることを試みて、エンティティを取得します
     pub fn try_uncover_tile(&mut self, coords: &Coordinates) -> Option<Entity> {
-        if self.marked_tiles.contains(coords) {
-            self.unmark_tile(coords);
+        let index = self.tile_map.index(*coords)?;
+        if !self.covered.contains(index) {
+            return None;
         }
-        self.covered_tiles.remove(coords)
+        self.marked.set(index, false);
+        self.flag_levels.remove(&index);
+        self.covered.set(index, false);
+        self.covered_count -= 1;
+        Some(self.tile_entities[index])
     }
 
-    /// 指定したマスがマーキングされていたらマークを外し、
-    /// されていなければマークをつける
+    /// 指定したマスの旗の数を1つ増やし、最大数を超えたらマークを外す
     ///
-    pub fn try_toggle_mark(&mut self, coords: &Coordinates) -> Option<(Entity, bool)> {
-        let entity = *self.covered_tiles.get(coords)?;
-        let mark = if self.marked_tiles.contains(coords) {
-            self.unmark_tile(coords)?;
-            false
+    /// 1つのタイルに爆弾が1つまでなら、マークをつけるか外すかの切り替えになる。
+    /// 新しい旗の数を返し、0ならマークが外れている
+    pub fn try_cycle_mark(&mut self, coords: &Coordinates) -> Option<(Entity, u8)> {
+        let entity = self.covered_entity(coords)?;
+        let index = self.tile_map.index(*coords)?;
+        let level = self.flag_levels.get(&index).copied().unwrap_or(0);
+        let level = if level >= self.tile_map.max_bombs_per_tile() { 0 } else { level + 1 };
+        self.marked.set(index, level > 0);
+        if level > 0 {
+            self.flag_levels.insert(index, level);
         } else {
-            self.marked_tiles.push(*coords);
-            true
-        };
-        Some((entity, mark))
+            self.flag_levels.remove(&index);
+        }
+        Some((entity, level))
+    }
+
+    /// `coords`のタイルを開き、空のタイルであれば繋がっている範囲を幅優先でまとめて開く
+    ///
+    /// 開いたタイルの座標とエンティティを`coords`からの距離の順に返す
+    pub fn uncover_region(&mut self, coords: Coordinates) -> Vec<(Coordinates, Entity)> {
+        let mut uncovered = Vec::new();
+        let mut queue = VecDeque::from([coords]);
+        while let Some(coords) = queue.pop_front() {
+            let Some(entity) = self.try_uncover_tile(&coords) else {
+                continue;
+            };
+            uncovered.push((coords, entity));
+            if self.tile_map.get(coords) == Some(Tile::Empty) {
+                queue.extend(
+                    self.tile_map
+                        .neighbors(coords)
+                        .filter(|c| self.is_covered(c)),
+                );
+            }
+        }
+        uncovered
     }
-    /// `coord`の隣接したエンティティを取得する
-    pub fn adjacent_covered_tiles(&self, coord: Coordinates) -> Vec<Entity> {
+
+    /// タイルをクリックしたときに開くタイル
+    ///
+    /// カバーされていて旗のないタイルはそのタイル、開いているタイルはコードで開く隣接マス
+    pub fn trigger_targets(&self, coords: Coordinates) -> Vec<Coordinates> {
+        if self.is_covered(&coords) {
+            self.tile_to_uncover(&coords).map(|_| coords).into_iter().collect()
+        } else {
+            self.chord_targets(coords)
+        }
+    }
+
+    /// 開いている数字のタイルの周囲の旗の合計が数字と同じであれば、旗のないカバーされた隣接マスを返す
+    pub fn chord_targets(&self, coords: Coordinates) -> Vec<Coordinates> {
+        let Some(Tile::BombNeighbor(count)) = self.tile_map.get(coords) else {
+            return Vec::new();
+        };
+        if self.is_covered(&coords) {
+            return Vec::new();
+        }
+        let marked: usize = self
+            .tile_map
+            .neighbors(coords)
+            .map(|c| self.flag_level(&c) as usize)
+            .sum();
+        if marked != count as usize {
+            return Vec::new();
+        }
         self.tile_map
-            .safe_square_at(coord)
-            .filter_map(|c| self.covered_tiles.get(&c))
-            .copied()
+            .neighbors(coords)
+            .filter(|c| self.is_covered(c) && !self.is_marked(c))
             .collect()
     }
-    /// `marked_tile`を一つ削除します
-    fn unmark_tile(&mut self, coords: &Coordinates) -> Option<Coordinates> {
-        let pos = match self.marked_tiles.iter().position(|a| a == coords) {
-            None => {
-                log::error!("Failed to unmark tile at {}", coords);
-                return None;
-            }
-            Some(p) => p,
-        };
 
-        Some(self.marked_tiles.remove(pos))
-    }
+    /// 爆弾のないタイルが全て開いているか、旗がどのタイルでも爆弾の数と同じか
+    ///
+    /// 存在しないマスは数えず、開いた爆弾のタイルは爆弾の数だけ旗が立っているとみなす
     pub fn is_completed(&self) -> bool {
-        self.tile_map.bomb_count() as usize == self.covered_tiles.len()
+        !self.is_lost() && (self.safe_tiles_uncovered() || self.flags_match_mines())
+    }
+
+    /// 爆弾のないタイルが全て開いているか
+    fn safe_tiles_uncovered(&self) -> bool {
+        self.tile_map.mined_tiles() == self.covered_count + self.revealed_mines.count_ones(..)
+    }
+
+    /// 旗の合計が爆弾の数と同じで、旗を立てたタイルの旗の数がどれも爆弾の数と同じか
+    fn flags_match_mines(&self) -> bool {
+        self.flag_total() == self.tile_map.bomb_count() as usize
+            && self
+                .flag_levels
+                .iter()
+                .all(|(i, level)| self.tile_map.bombs_at(self.tile_map.coordinates(*i)) == *level)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// シードで爆弾を置いた、描画しないボード
+    fn board(size: (u16, u16), bomb_count: u32, max_per_tile: u8, seed: u64) -> Board {
+        let mut tile_map = TileMap::empty(size.0, size.1);
+        tile_map.set_bombs_seeded(bomb_count, max_per_tile, seed);
+        let tile_entities = vec![Entity::PLACEHOLDER; tile_map.len()];
+        Board::new(
+            tile_map,
+            Bounds2 { position: Vec2::ZERO, size: Vec2::ZERO },
+            1.,
+            Entity::PLACEHOLDER,
+            RenderMode::Entities,
+            GridShape::Square,
+            tile_entities,
+        )
+    }
+
+    #[test]
+    fn empty_board_opens_as_one_region() {
+        let mut board = board((5, 4), 0, 1, 0);
+        let start = Coordinates { x: 2, y: 1 };
+        let uncovered = board.uncover_region(start);
+        assert_eq!(uncovered.len(), 20);
+        assert_eq!(uncovered[0].0, start);
+        assert!(board.is_completed());
+        // 開いたタイルはもう開かない
+        assert!(board.uncover_region(Coordinates { x: 0, y: 0 }).is_empty());
+    }
+
+    #[test]
+    fn numbers_and_mines_open_alone() {
+        let mut board = board((6, 6), 6, 1, 0);
+        let coords: Vec<_> = (0..6)
+            .flat_map(|y| (0..6).map(move |x| Coordinates { x, y }))
+            .collect();
+        let tile_map = &board.tile_map;
+        let mine = *coords.iter().find(|c| tile_map.is_bomb_at(**c)).unwrap();
+        let number = *coords
+            .iter()
+            .find(|c| !tile_map.is_bomb_at(**c) && tile_map.bomb_count_at(**c) > 0)
+            .unwrap();
+        assert_eq!(board.uncover_region(mine).len(), 1);
+        assert_eq!(board.uncover_region(number).len(), 1);
+    }
+
+    /// 条件に合うボードが見つかるまでシードを変える
+    fn board_where(
+        size: (u16, u16),
+        bomb_count: u32,
+        max_per_tile: u8,
+        matches: impl Fn(&Board) -> bool,
+    ) -> Board {
+        (0..1000)
+            .map(|seed| board(size, bomb_count, max_per_tile, seed))
+            .find(matches)
+            .expect("no seed matches")
+    }
+
+    /// 旗の数が`level`になるまで切り替える
+    fn mark_to(board: &mut Board, coords: Coordinates, level: u8) {
+        while board.flag_level(&coords) != level {
+            board.try_cycle_mark(&coords).expect("covered tile");
+        }
     }
-}
\ No newline at end of file
+
+    fn tile_with(board: &Board, matches: impl Fn(Tile) -> bool) -> Coordinates {
+        let found = board.tile_map.iter().find(|(_, t)| matches(*t));
+        found.map(|(c, _)| c).expect("tile")
+    }
+
+    #[test]
+    fn mark_cycles_back_to_zero_after_max() {
+        let mut board = board((4, 4), 3, 3, 0);
+        let coords = Coordinates { x: 0, y: 0 };
+        let levels: Vec<u8> = (0..4).map(|_| board.try_cycle_mark(&coords).unwrap().1).collect();
+        assert_eq!(levels, vec![1, 2, 3, 0]);
+        assert!(!board.is_marked(&coords));
+        assert_eq!(board.flag_total(), 0);
+    }
+
+    #[test]
+    fn single_mine_mark_toggles() {
+        let mut board = board((4, 4), 3, 1, 0);
+        let coords = Coordinates { x: 1, y: 1 };
+        assert_eq!(board.try_cycle_mark(&coords).map(|(_, l)| l), Some(1));
+        assert!(board.is_marked(&coords));
+        assert_eq!(board.try_cycle_mark(&coords).map(|(_, l)| l), Some(0));
+        assert!(!board.is_marked(&coords));
+    }
+
+    #[test]
+    fn uncovered_tiles_cannot_be_marked() {
+        let mut board = board((4, 4), 3, 1, 0);
+        let coords = tile_with(&board, |t| !t.is_bomb());
+        board.try_uncover_tile(&coords);
+        assert_eq!(board.try_cycle_mark(&coords), None);
+    }
+
+    /// 2つの爆弾があるタイルに隣接し、爆弾のない隣接マスもある数字のタイル
+    fn chord_tile(board: &Board) -> Option<Coordinates> {
+        let tile_map = &board.tile_map;
+        let found = tile_map.iter().find(|(c, t)| {
+            matches!(t, Tile::BombNeighbor(_))
+                && tile_map.neighbors(*c).any(|n| tile_map.bombs_at(n) == 2)
+                && tile_map.neighbors(*c).any(|n| !tile_map.is_bomb_at(n))
+        });
+        found.map(|(c, _)| c)
+    }
+
+    #[test]
+    fn chord_targets_sum_flag_levels() {
+        let mut board = board_where((5, 5), 6, 2, |b| chord_tile(b).is_some());
+        let number = chord_tile(&board).unwrap();
+        board.try_uncover_tile(&number);
+        let mines: Vec<_> = board
+            .tile_map
+            .neighbors(number)
+            .filter(|c| board.tile_map.is_bomb_at(*c))
+            .collect();
+        let double = *mines.iter().find(|c| board.tile_map.bombs_at(**c) == 2).unwrap();
+
+        // 2つの爆弾のタイルに旗が1つでは足りない
+        for mine in &mines {
+            let bombs = board.tile_map.bombs_at(*mine);
+            mark_to(&mut board, *mine, bombs);
+        }
+        mark_to(&mut board, double, 1);
+        assert!(board.chord_targets(number).is_empty());
+
+        mark_to(&mut board, double, 2);
+        let safe: Vec<_> = board
+            .tile_map
+            .neighbors(number)
+            .filter(|c| !board.tile_map.is_bomb_at(*c) && board.is_covered(c))
+            .collect();
+        assert!(!safe.is_empty());
+        assert_eq!(board.chord_targets(number), safe);
+    }
+
+    #[test]
+    fn uncovering_every_safe_tile_completes() {
+        let mut board = board((4, 4), 3, 2, 0);
+        let safe: Vec<_> = board
+            .tile_map
+            .iter()
+            .filter(|(_, t)| !t.is_bomb())
+            .map(|(c, _)| c)
+            .collect();
+        for coords in safe {
+            assert!(!board.is_completed());
+            board.try_uncover_tile(&coords);
+        }
+        assert!(board.is_completed());
+    }
+
+    #[test]
+    fn completed_needs_every_flag_level_right() {
+        // 爆弾2つのタイルと1つのタイルだけのボード
+        let mut board = board_where((4, 4), 3, 2, |b| b.tile_map.mined_tiles() == 2);
+        let double = tile_with(&board, |t| t == Tile::Bomb(2));
+        let single = tile_with(&board, |t| t == Tile::Bomb(1));
+
+        // 旗の合計は爆弾の数と同じだが、タイルごとの数が違う
+        mark_to(&mut board, double, 1);
+        mark_to(&mut board, single, 2);
+        assert_eq!(board.flag_total(), 3);
+        assert!(!board.is_completed());
+
+        mark_to(&mut board, double, 2);
+        mark_to(&mut board, single, 1);
+        assert!(board.is_completed());
+    }
+
+    #[test]
+    fn detonating_costs_one_life_and_flags_the_mine() {
+        let mut board = board((4, 4), 3, 2, 0).with_lives(3);
+        let mine = tile_with(&board, |t| t.is_bomb());
+        board.try_uncover_tile(&mine);
+        assert_eq!(board.detonate(&mine), 2);
+        assert_eq!(board.flag_level(&mine), board.tile_map.bombs_at(mine));
+        assert_eq!(board.flag_total(), board.tile_map.bombs_at(mine) as usize);
+        // 同じ爆弾で2回はライフを失わない
+        assert_eq!(board.detonate(&mine), 2);
+        assert_eq!(board.lives_lost(), 1);
+        assert!(!board.is_lost());
+    }
+
+    #[test]
+    fn losing_every_life_loses_the_board() {
+        let mut board = board_where((4, 4), 3, 1, |b| b.tile_map.mined_tiles() == 3).with_lives(2);
+        let mines: Vec<_> = board
+            .tile_map
+            .iter()
+            .filter(|(_, t)| t.is_bomb())
+            .map(|(c, _)| c)
+            .collect();
+        assert_eq!(board.detonate(&mines[0]), 1);
+        assert!(!board.is_lost());
+        assert_eq!(board.detonate(&mines[1]), 0);
+        assert_eq!(board.lives_lost(), 2);
+        assert!(board.is_lost());
+        assert!(!board.is_completed());
+        // ライフがなくなっても減り続けない
+        assert_eq!(board.detonate(&mines[2]), 0);
+        assert_eq!(board.lives_lost(), 2);
+    }
+
+    #[test]
+    fn detonated_mines_count_toward_completion() {
+        let mut board = board((4, 4), 3, 1, 0).with_lives(3);
+        let mine = tile_with(&board, |t| t.is_bomb());
+        board.try_uncover_tile(&mine);
+        board.detonate(&mine);
+        let safe: Vec<_> = board
+            .tile_map
+            .iter()
+            .filter(|(_, t)| !t.is_bomb())
+            .map(|(c, _)| c)
+            .collect();
+        for coords in safe {
+            board.try_uncover_tile(&coords);
+        }
+        assert!(board.is_completed());
+    }
+}
@@ -1,5 +1,9 @@
-use bevy::color::palettes::css::{GREEN, ORANGE, PURPLE, WHITE, YELLOW};
+use bevy::color::palettes::css::{AQUA, BLUE, DARK_GRAY, GRAY, HOT_PINK, LIME, ORANGE, RED, SILVER, VIOLET, WHITE, YELLOW};
+use std::collections::HashMap;
+use bevy::asset::UntypedAssetId;
 use bevy::prelude::*;
+use crate::resources::default_visuals::{COVER_IMAGE, FLAG_IMAGE, MINE_IMAGE};
+use crate::resources::sound::SoundCue;
 
 /// テクスチャと色を持ったスプライトのマテリアル
 #[derive(Debug, Clone)]
@@ -17,6 +21,13 @@ impl Default for SpriteMaterial {
     }
 }
 
+/// 1から8の数字を順に並べたテクスチャアトラス
+#[derive(Debug, Clone)]
+pub struct DigitAtlas {
+    pub texture: Handle<Image>,
+    pub layout: Handle<TextureAtlasLayout>,
+}
+
 /// ボードのアセット、リソースとして扱う
 #[derive(Debug, Clone, Resource)]
 pub struct BoardAssets {
@@ -26,20 +37,121 @@ pub struct BoardAssets {
     pub covered_tile_material: SpriteMaterial,
     pub bomb_counter_font: Handle<Font>,
     pub bomb_counter_colors: Vec<Color>,
+    /// あれば1から8の数字はフォントの代わりにアトラスのスプライトで描く
+    pub digit_atlas: Option<DigitAtlas>,
     pub flag_material: SpriteMaterial,
     pub bomb_material: SpriteMaterial,
+    /// 対戦モードでのプレイヤーごとの旗の色
+    pub player_flag_colors: Vec<Color>,
+    /// 音の種類ごとの音声ファイルのパス、なければ鳴らさない
+    pub sounds: HashMap<SoundCue, String>,
+    /// 数字と旗に下地と数の印を付ける
+    ///
+    /// チャンク描画では黒い下地だけになる、8ピクセルのタイルでは数の印を見分けられない
+    pub high_contrast: bool,
+}
+
+/// アセットのファイルを使わない組み込みの見た目、数字はフォントの代わりにスプライトで描く
+impl Default for BoardAssets {
+    fn default() -> Self {
+        Self {
+            label: "Built-in".to_string(),
+            board_material: SpriteMaterial {
+                color: Color::WHITE,
+                ..Default::default()
+            },
+            tile_material: SpriteMaterial {
+                color: Color::from(DARK_GRAY),
+                ..Default::default()
+            },
+            covered_tile_material: SpriteMaterial {
+                color: Color::from(GRAY),
+                texture: COVER_IMAGE,
+            },
+            bomb_counter_font: Default::default(),
+            bomb_counter_colors: Self::default_colors(),
+            digit_atlas: None,
+            flag_material: SpriteMaterial {
+                color: Color::from(RED),
+                texture: FLAG_IMAGE,
+            },
+            bomb_material: SpriteMaterial {
+                color: Color::WHITE,
+                texture: MINE_IMAGE,
+            },
+            player_flag_colors: Self::default_player_flag_colors(),
+            sounds: HashMap::new(),
+            high_contrast: false,
+        }
+    }
 }
 
 impl BoardAssets {
+    /// 1から8の数字の色
     pub fn default_colors() -> Vec<Color> {
         vec![
             Color::WHITE,
-            Color::from(GREEN),
+            Color::from(LIME),
             Color::from(YELLOW),
             Color::from(ORANGE),
-            Color::from(PURPLE),
+            Color::from(VIOLET),
+            Color::from(AQUA),
+            Color::from(HOT_PINK),
+            Color::from(SILVER),
         ]
     }
+    pub fn default_player_flag_colors() -> Vec<Color> {
+        vec![Color::from(RED), Color::from(BLUE)]
+    }
+
+    /// プレイヤーの旗の色、足りなければ旗の色
+    pub fn player_flag_color(&self, player: u8) -> Color {
+        self.player_flag_colors
+            .get(player as usize)
+            .copied()
+            .unwrap_or(self.flag_material.color)
+    }
+
+    /// 読み込みを待つハンドル
+    pub fn handles(&self) -> Vec<UntypedAssetId> {
+        let atlas = self.digit_atlas.iter().map(|atlas| atlas.texture.id().untyped());
+        atlas.chain([
+            self.bomb_counter_font.id().untyped(),
+            self.board_material.texture.id().untyped(),
+            self.tile_material.texture.id().untyped(),
+            self.covered_tile_material.texture.id().untyped(),
+            self.flag_material.texture.id().untyped(),
+            self.bomb_material.texture.id().untyped(),
+        ]).collect()
+    }
+
+    /// 読み込めなかったハンドルを組み込みの見た目に戻す
+    pub fn reset_handle(&mut self, id: UntypedAssetId) {
+        let builtin = Self::default();
+        if self.bomb_counter_font.id().untyped() == id {
+            self.bomb_counter_font = builtin.bomb_counter_font;
+        }
+        if self.digit_atlas.as_ref().is_some_and(|atlas| atlas.texture.id().untyped() == id) {
+            self.digit_atlas = builtin.digit_atlas;
+        }
+        for (material, builtin) in [
+            (&mut self.board_material, builtin.board_material),
+            (&mut self.tile_material, builtin.tile_material),
+            (&mut self.covered_tile_material, builtin.covered_tile_material),
+            (&mut self.flag_material, builtin.flag_material),
+            (&mut self.bomb_material, builtin.bomb_material),
+        ] {
+            if material.texture.id().untyped() == id {
+                material.texture = builtin.texture;
+            }
+        }
+    }
+
+    /// 数字のフォントがあるか、なければ数字は組み込みのスプライトで描く
+    pub fn has_counter_font(&self) -> bool {
+        self.bomb_counter_font != Handle::default()
+    }
+
     /// 爆弾カウンターに一致する色を安全に取得する
     pub fn bomb_counter_color(&self, counter: u8) -> Color {
         let counter = counter.saturating_sub(1) as usize;
@@ -51,4 +163,60 @@ impl BoardAssets {
             },
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn material(texture: u128) -> SpriteMaterial {
+        SpriteMaterial {
+            color: Color::WHITE,
+            texture: Handle::weak_from_u128(texture),
+        }
+    }
+
+    /// 全てのハンドルがテーマのファイルを指すアセット
+    fn board_assets() -> BoardAssets {
+        BoardAssets {
+            board_material: material(1),
+            tile_material: material(2),
+            covered_tile_material: material(3),
+            bomb_counter_font: Handle::weak_from_u128(4),
+            flag_material: material(5),
+            bomb_material: material(6),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn failed_handle_falls_back_to_the_builtin_one() {
+        let builtin = BoardAssets::default();
+        let mut board_assets = board_assets();
+        let flag = board_assets.flag_material.texture.id().untyped();
+        assert!(board_assets.handles().contains(&flag));
+        board_assets.reset_handle(flag);
+        assert_eq!(board_assets.flag_material.texture, builtin.flag_material.texture);
+        assert_eq!(board_assets.bomb_material.texture, Handle::weak_from_u128(6));
+
+        // フォントがなければ数字は組み込みのスプライトで描く
+        let font = board_assets.bomb_counter_font.id().untyped();
+        board_assets.reset_handle(font);
+        assert!(!board_assets.has_counter_font());
+    }
+
+    #[test]
+    fn failed_digit_atlas_is_dropped() {
+        let mut board_assets = BoardAssets {
+            digit_atlas: Some(DigitAtlas {
+                texture: Handle::weak_from_u128(7),
+                layout: Handle::default(),
+            }),
+            ..Default::default()
+        };
+        let atlas = board_assets.digit_atlas.as_ref().unwrap().texture.id().untyped();
+        assert!(board_assets.handles().contains(&atlas));
+        board_assets.reset_handle(atlas);
+        assert!(board_assets.digit_atlas.is_none());
+    }
+}
@@ -1,5 +1,9 @@
-use bevy::prelude::{Resource, Vec3};
+use std::sync::Arc;
+use bevy::prelude::{Component, Resource, Vec2, Vec3};
 use serde::{Deserialize, Serialize};
+use crate::resources::grid_shape::GridShape;
+use crate::resources::shape_mask::BoardShape;
+use crate::resources::topology::{Neighborhood, Topology, Toroidal};
 
 /// タイルの大きさのオプション
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -15,22 +19,123 @@ pub enum BoardPosition {
     Custom(Vec3),
 }
 
+/// タイルの描画方法
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum RenderMode {
+    /// タイルごとにエンティティを生成する
+    #[default]
+    Entities,
+    /// `chunk_size`四方のタイルを1枚のテクスチャにまとめて描画する
+    ///
+    /// 六角形のマスと、1つのタイルに爆弾を2つ以上置くボードでは`Entities`になる。
+    /// ハイコントラストでも数と旗の印は付かない
+    Chunked { chunk_size: u16 },
+}
+
+/// ボードの端の扱い
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum BoardEdges {
+    #[default]
+    Bounded,
+    /// 端が反対側の端と繋がる、`ghosts`で反対側の列と行の複製を外側に描画する
+    Wrapping { ghosts: bool },
+}
+
+/// 遊び方
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum GameMode {
+    /// 1人で爆弾以外の全てのタイルを開く
+    #[default]
+    Classic,
+    /// 2人が交互にタイルを開き、爆弾を多く見つけた方が勝つ
+    Flags,
+}
+
 /// リソースとして使用する必要があるボード生成オプション
+///
+/// 生成したボードのエンティティにもシードを決めてコンポーネントとして付け、大きさの変更とやり直しに使う
 // serdeを利用したことでプリセットを実行時にロードできます
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Component, Serialize, Deserialize)]
 pub struct BoardOptions {
     pub map_size: (u16, u16),
-    pub bomb_count: u16,
+    pub bomb_count: u32,
+    /// 1つのタイルに置ける爆弾の最大数
+    pub max_bombs_per_tile: u8,
     pub position: BoardPosition,
     pub tile_size: TileSize,
     pub tile_padding: f32,
     pub safe_start: bool,
+    pub render_mode: RenderMode,
+    pub neighborhood: Neighborhood,
+    pub grid_shape: GridShape,
+    pub edges: BoardEdges,
+    /// ボードの形、マスクで除かれたマスは存在しない
+    pub shape: BoardShape,
+    /// 爆発に耐えられる回数、1なら最初の爆発でゲームオーバー
+    pub lives: u8,
+    /// 爆弾の配置のシード、`None`なら毎回ランダム
+    pub seed: Option<u64>,
+    pub mode: GameMode,
+    /// タイルを開く、旗を立てる、爆発するときのアニメーション、ゲームの状態は待たずに変わる
+    pub animations: bool,
 }
 
 impl Resource for BoardOptions {
 
 }
 
+impl TileSize {
+    /// ウィンドウの大きさとタイル単位でのボードの大きさからタイルの大きさを計算する
+    pub fn compute(&self, window_size: Vec2, board_tiles: Vec2) -> f32 {
+        match self {
+            TileSize::Fixed(v) => *v,
+            TileSize::Adaptive { min, max } => {
+                let max_width = window_size.x / board_tiles.x;
+                let max_height = window_size.y / board_tiles.y;
+                max_width.min(max_height).clamp(*min, *max)
+            }
+        }
+    }
+}
+
+impl BoardPosition {
+    /// ボードの大きさから左下の座標を計算する
+    pub fn translation(&self, board_size: Vec2) -> Vec3 {
+        match self {
+            BoardPosition::Centered { offset } => {
+                Vec3::new(-(board_size.x / 2.), -(board_size.y / 2.), 0.) + *offset
+            }
+            BoardPosition::Custom(p) => *p,
+        }
+    }
+}
+
+impl BoardOptions {
+    /// マスの形と端の扱いを合わせた隣接関係
+    pub fn topology(&self) -> Arc<dyn Topology> {
+        let topology = self.grid_shape.topology(&self.neighborhood);
+        match self.edges {
+            BoardEdges::Bounded => topology,
+            BoardEdges::Wrapping { .. } => Arc::new(Toroidal(topology)),
+        }
+    }
+
+    /// 端の複製を描画するか
+    pub fn ghosts(&self) -> bool {
+        matches!(self.edges, BoardEdges::Wrapping { ghosts: true })
+    }
+
+    /// 複製を含めた、タイル単位での描画範囲の大きさ
+    pub fn board_tiles(&self) -> Vec2 {
+        let size = self.grid_shape.board_size(self.map_size, 1.);
+        if self.ghosts() {
+            size + Vec2::splat(2.)
+        } else {
+            size
+        }
+    }
+}
+
 impl Default for TileSize {
     fn default() -> Self {
         Self::Adaptive {
@@ -52,10 +157,43 @@ impl Default for BoardOptions {
         Self {
             map_size: (15, 15),
             bomb_count: 30,
+            max_bombs_per_tile: 1,
             position: Default::default(),
             tile_size: Default::default(),
             tile_padding: 0.,
             safe_start: false,
+            render_mode: Default::default(),
+            neighborhood: Default::default(),
+            grid_shape: Default::default(),
+            edges: Default::default(),
+            shape: Default::default(),
+            lives: 1,
+            seed: None,
+            mode: Default::default(),
+            animations: true,
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adaptive_tiles_fit_the_shorter_side() {
+        let tile_size = TileSize::Adaptive { min: 10., max: 50. };
+        assert_eq!(tile_size.compute(Vec2::new(800., 600.), Vec2::new(20., 20.)), 30.);
+        // 範囲の外は端の大きさにする
+        assert_eq!(tile_size.compute(Vec2::new(100., 100.), Vec2::new(20., 20.)), 10.);
+        assert_eq!(tile_size.compute(Vec2::new(2000., 2000.), Vec2::new(10., 10.)), 50.);
+        assert_eq!(TileSize::Fixed(12.).compute(Vec2::new(800., 600.), Vec2::new(20., 20.)), 12.);
+    }
+
+    #[test]
+    fn centered_board_surrounds_the_origin() {
+        let position = BoardPosition::Centered { offset: Vec3::new(0., 10., 1.) };
+        assert_eq!(position.translation(Vec2::new(200., 100.)), Vec3::new(-100., -40., 1.));
+        let custom = Vec3::new(5., 6., 7.);
+        assert_eq!(BoardPosition::Custom(custom).translation(Vec2::new(200., 100.)), custom);
+    }
+}
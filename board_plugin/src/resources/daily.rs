@@ -0,0 +1,235 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+use bevy::prelude::Resource;
+use serde::{Deserialize, Serialize};
+use crate::components::Coordinates;
+use crate::resources::board_options::{BoardOptions, GameMode};
+
+/// デイリーチャレンジの結果を保存するファイル
+pub const DAILY_RESULTS_PATH: &str = "daily_results.json";
+
+/// UTCの日付、1970-01-01からの日数で持つ
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct Date(pub i64);
+
+impl Date {
+    pub fn today() -> Self {
+        let seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        Self((seconds / 86_400) as i64)
+    }
+
+    pub fn previous(self) -> Self {
+        Self(self.0 - 1)
+    }
+
+    /// 年、月、日
+    pub fn ymd(self) -> (i64, u32, u32) {
+        // http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+        let z = self.0 + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z.rem_euclid(146_097);
+        let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let year = yoe + era * 400 + i64::from(month <= 2);
+        (year, month, day)
+    }
+}
+
+impl Display for Date {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let (year, month, day) = self.ymd();
+        write!(f, "{:04}-{:02}-{:02}", year, month, day)
+    }
+}
+
+/// デイリーチャレンジの難易度
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum DailyDifficulty {
+    Easy,
+    Normal,
+    Hard,
+}
+
+impl DailyDifficulty {
+    /// マップの大きさと爆弾の数
+    pub fn preset(&self) -> ((u16, u16), u32) {
+        match self {
+            DailyDifficulty::Easy => ((9, 9), 10),
+            DailyDifficulty::Normal => ((16, 16), 40),
+            DailyDifficulty::Hard => ((30, 16), 99),
+        }
+    }
+}
+
+/// 日付から決まるデイリーチャレンジ、リソースとして存在する間はデイリーモード
+///
+/// シードと難易度は日付だけから計算するので、同じ日なら誰でも同じボードになる
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Resource)]
+pub struct DailyChallenge {
+    pub date: Date,
+    pub seed: u64,
+    pub difficulty: DailyDifficulty,
+}
+
+impl DailyChallenge {
+    pub fn today() -> Self {
+        Self::for_date(Date::today())
+    }
+
+    pub fn for_date(date: Date) -> Self {
+        let seed = splitmix64(date.0 as u64);
+        let difficulty = match seed % 3 {
+            0 => DailyDifficulty::Easy,
+            1 => DailyDifficulty::Normal,
+            _ => DailyDifficulty::Hard,
+        };
+        Self { date, seed, difficulty }
+    }
+
+    /// `base`の見た目の設定を残して、マップと爆弾の配置をデイリーチャレンジのものにする
+    pub fn board_options(&self, base: &BoardOptions) -> BoardOptions {
+        let (map_size, bomb_count) = self.difficulty.preset();
+        BoardOptions {
+            map_size,
+            bomb_count,
+            max_bombs_per_tile: 1,
+            safe_start: true,
+            lives: 1,
+            seed: Some(self.seed),
+            mode: GameMode::Classic,
+            neighborhood: Default::default(),
+            grid_shape: Default::default(),
+            edges: Default::default(),
+            shape: Default::default(),
+            ..base.clone()
+        }
+    }
+}
+
+/// 日付をシードに混ぜる、実装が変わると過去の日のボードも変わるので変更しない
+fn splitmix64(value: u64) -> u64 {
+    let mut z = value.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// プレイヤーの操作
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum ReplayAction {
+    Uncover(Coordinates),
+    Mark(Coordinates),
+}
+
+/// リプレイの1つの操作とボードを生成してからの秒数
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReplayStep {
+    pub seconds: f32,
+    pub action: ReplayAction,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum DailyOutcome {
+    Won,
+    Lost,
+}
+
+/// 1日の結果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DailyResult {
+    pub seconds: f32,
+    pub outcome: DailyOutcome,
+    pub replay: Vec<ReplayStep>,
+}
+
+/// 日付ごとのデイリーチャレンジの結果、1日に1つだけ残す
+#[derive(Debug, Clone, Default, Resource, Serialize, Deserialize)]
+pub struct DailyResults {
+    pub results: BTreeMap<Date, DailyResult>,
+}
+
+impl DailyResults {
+    /// ファイルから読み込む、読めなければ空の結果
+    pub fn load(path: impl AsRef<Path>) -> Self {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)
+    }
+
+    /// その日の最初の結果だけを記録する、記録したら`true`
+    pub fn record(&mut self, date: Date, result: DailyResult) -> bool {
+        if self.results.contains_key(&date) {
+            return false;
+        }
+        self.results.insert(date, result);
+        true
+    }
+
+    /// `today`までクリアが続いている日数、今日がまだなら昨日までを数える
+    pub fn streak(&self, today: Date) -> u32 {
+        let won = |date: Date| {
+            self.results
+                .get(&date)
+                .is_some_and(|r| r.outcome == DailyOutcome::Won)
+        };
+        let mut date = if self.results.contains_key(&today) { today } else { today.previous() };
+        let mut streak = 0;
+        while won(date) {
+            streak += 1;
+            date = date.previous();
+        }
+        streak
+    }
+}
+
+/// 今のデイリーチャレンジの操作の記録
+#[derive(Debug, Clone, Default, Resource)]
+pub struct DailyReplay(pub Vec<ReplayStep>);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn date_is_shown_as_utc_day() {
+        assert_eq!(Date(0).to_string(), "1970-01-01");
+        assert_eq!(Date(19_723).to_string(), "2024-01-01");
+    }
+
+    #[test]
+    fn challenge_is_the_same_across_runs() {
+        // 値が変わると過去の日のボードとリプレイが変わってしまう
+        let challenges = [
+            (Date(0), 0xE220_A839_7B1D_CDAF, DailyDifficulty::Normal),
+            (Date(19_723), 0x47A4_C66C_FE6D_DE15, DailyDifficulty::Normal),
+            (Date(19_724), 0x596C_AD38_C0CD_30D6, DailyDifficulty::Hard),
+            (Date(19_732), 0xAE4D_249A_66EA_78C3, DailyDifficulty::Easy),
+        ];
+        for (date, seed, difficulty) in challenges {
+            let challenge = DailyChallenge::for_date(date);
+            assert_eq!(challenge, DailyChallenge { date, seed, difficulty }, "{}", date);
+        }
+    }
+
+    #[test]
+    fn board_options_use_the_daily_board() {
+        let challenge = DailyChallenge::for_date(Date(19_724));
+        let options = challenge.board_options(&BoardOptions::default());
+        assert_eq!(options.map_size, (30, 16));
+        assert_eq!(options.bomb_count, 99);
+        assert_eq!(options.seed, Some(challenge.seed));
+    }
+}
@@ -0,0 +1,268 @@
+use bevy::prelude::*;
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy::render::texture::ImageSampler;
+
+/// 組み込みのテクスチャの1辺のピクセル数
+pub const IMAGE_SIZE: u32 = 32;
+
+/// 面取りしたカバーのテクスチャ
+pub const COVER_IMAGE: Handle<Image> = Handle::weak_from_u128(0x6d1e_0c3a_54f2_4b8e_9a10_2f7e_1c44_b001);
+/// 旗のテクスチャ、布は白いのでマテリアルの色で塗る
+pub const FLAG_IMAGE: Handle<Image> = Handle::weak_from_u128(0x6d1e_0c3a_54f2_4b8e_9a10_2f7e_1c44_b002);
+/// 爆弾のテクスチャ
+pub const MINE_IMAGE: Handle<Image> = Handle::weak_from_u128(0x6d1e_0c3a_54f2_4b8e_9a10_2f7e_1c44_b003);
+/// やり直しボタンの笑顔
+pub const FACE_IMAGE: Handle<Image> = Handle::weak_from_u128(0x6d1e_0c3a_54f2_4b8e_9a10_2f7e_1c44_b004);
+/// クリアしたときのサングラスの顔
+pub const FACE_WON_IMAGE: Handle<Image> = Handle::weak_from_u128(0x6d1e_0c3a_54f2_4b8e_9a10_2f7e_1c44_b005);
+/// ゲームオーバーのときの目がバツの顔
+pub const FACE_LOST_IMAGE: Handle<Image> = Handle::weak_from_u128(0x6d1e_0c3a_54f2_4b8e_9a10_2f7e_1c44_b006);
+/// 0から9の数字のテクスチャ、白いので数字の色で塗る
+pub const DIGIT_IMAGES: [Handle<Image>; 10] = [
+    Handle::weak_from_u128(0x6d1e_0c3a_54f2_4b8e_9a10_2f7e_1c44_b100),
+    Handle::weak_from_u128(0x6d1e_0c3a_54f2_4b8e_9a10_2f7e_1c44_b101),
+    Handle::weak_from_u128(0x6d1e_0c3a_54f2_4b8e_9a10_2f7e_1c44_b102),
+    Handle::weak_from_u128(0x6d1e_0c3a_54f2_4b8e_9a10_2f7e_1c44_b103),
+    Handle::weak_from_u128(0x6d1e_0c3a_54f2_4b8e_9a10_2f7e_1c44_b104),
+    Handle::weak_from_u128(0x6d1e_0c3a_54f2_4b8e_9a10_2f7e_1c44_b105),
+    Handle::weak_from_u128(0x6d1e_0c3a_54f2_4b8e_9a10_2f7e_1c44_b106),
+    Handle::weak_from_u128(0x6d1e_0c3a_54f2_4b8e_9a10_2f7e_1c44_b107),
+    Handle::weak_from_u128(0x6d1e_0c3a_54f2_4b8e_9a10_2f7e_1c44_b108),
+    Handle::weak_from_u128(0x6d1e_0c3a_54f2_4b8e_9a10_2f7e_1c44_b109),
+];
+
+/// 3x5の数字のグリフ、0から9の順で各行の下位3ビットを使う
+pub(crate) const DIGIT_GLYPHS: [[u8; 5]; 10] = [
+    [0b111, 0b101, 0b101, 0b101, 0b111],
+    [0b010, 0b110, 0b010, 0b010, 0b111],
+    [0b111, 0b001, 0b111, 0b100, 0b111],
+    [0b111, 0b001, 0b111, 0b001, 0b111],
+    [0b101, 0b101, 0b111, 0b001, 0b001],
+    [0b111, 0b100, 0b111, 0b001, 0b111],
+    [0b111, 0b100, 0b111, 0b101, 0b111],
+    [0b111, 0b001, 0b001, 0b010, 0b010],
+    [0b111, 0b101, 0b111, 0b101, 0b111],
+    [0b111, 0b101, 0b111, 0b001, 0b111],
+];
+
+/// 組み込みのテクスチャを全て`images`に追加する
+pub fn insert_default_visuals(images: &mut Assets<Image>) {
+    images.insert(&COVER_IMAGE, cover_image());
+    images.insert(&FLAG_IMAGE, flag_image());
+    images.insert(&MINE_IMAGE, mine_image());
+    images.insert(&FACE_IMAGE, face_image(Mood::Happy));
+    images.insert(&FACE_WON_IMAGE, face_image(Mood::Won));
+    images.insert(&FACE_LOST_IMAGE, face_image(Mood::Lost));
+    for (digit, handle) in DIGIT_IMAGES.iter().enumerate() {
+        images.insert(handle, digit_image(digit));
+    }
+}
+
+/// 透明な`IMAGE_SIZE`四方の画像
+fn canvas() -> Image {
+    Image::new_fill(
+        Extent3d {
+            width: IMAGE_SIZE,
+            height: IMAGE_SIZE,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &[0, 0, 0, 0],
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    )
+}
+
+/// 左上が原点のピクセルを塗る
+fn put(image: &mut Image, x: u32, y: u32, color: [u8; 4]) {
+    let i = ((y * IMAGE_SIZE + x) * 4) as usize;
+    image.data[i..i + 4].copy_from_slice(&color);
+}
+
+fn gray(value: u8) -> [u8; 4] {
+    [value, value, value, 255]
+}
+
+/// 左上が明るく右下が暗い縁のある灰色のタイル
+fn cover_image() -> Image {
+    const BEVEL: u32 = 3;
+    let mut image = canvas();
+    let last = IMAGE_SIZE - 1;
+    for y in 0..IMAGE_SIZE {
+        for x in 0..IMAGE_SIZE {
+            let light = x < BEVEL || y < BEVEL;
+            let dark = x > last - BEVEL || y > last - BEVEL;
+            // 角は対角線で明るい縁と暗い縁に分ける
+            let value = match (light, dark) {
+                (true, true) if x + y < last => 255,
+                (true, true) => 130,
+                (true, false) => 255,
+                (false, true) => 130,
+                (false, false) => 200,
+            };
+            put(&mut image, x, y, gray(value));
+        }
+    }
+    image
+}
+
+/// 台座と竿と三角の布の旗
+fn flag_image() -> Image {
+    let mut image = canvas();
+    let pole = gray(70);
+    for y in 5..26 {
+        put(&mut image, 15, y, pole);
+        put(&mut image, 16, y, pole);
+    }
+    for y in 26..28 {
+        for x in 9..24 {
+            put(&mut image, x, y, pole);
+        }
+    }
+    // 竿から左に伸びる三角形
+    for y in 5..17u32 {
+        let width = 8 - (y as i32 - 11).unsigned_abs().min(6);
+        for x in (15 - width)..15 {
+            put(&mut image, x, y, gray(255));
+        }
+    }
+    image
+}
+
+/// 8方向にとげのある丸い爆弾
+fn mine_image() -> Image {
+    let mut image = canvas();
+    let center = IMAGE_SIZE as f32 / 2. - 0.5;
+    let body = gray(40);
+    for y in 0..IMAGE_SIZE {
+        for x in 0..IMAGE_SIZE {
+            let (dx, dy) = (x as f32 - center, y as f32 - center);
+            let distance = (dx * dx + dy * dy).sqrt();
+            let spike = (dx.abs() < 1. || dy.abs() < 1. || (dx.abs() - dy.abs()).abs() < 1.2) && distance < 14.;
+            if distance < 9. || spike {
+                put(&mut image, x, y, body);
+            }
+        }
+    }
+    // 左上の光
+    for y in 11..14 {
+        for x in 11..14 {
+            put(&mut image, x, y, gray(255));
+        }
+    }
+    image
+}
+
+/// 顔の表情
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Mood {
+    Happy,
+    Won,
+    Lost,
+}
+
+/// 黄色い丸に目と口を描いた顔
+fn face_image(mood: Mood) -> Image {
+    let mut image = canvas();
+    let center = IMAGE_SIZE as f32 / 2. - 0.5;
+    let line = gray(40);
+    for y in 0..IMAGE_SIZE {
+        for x in 0..IMAGE_SIZE {
+            let distance = (x as f32 - center).hypot(y as f32 - center);
+            if distance < 13. {
+                put(&mut image, x, y, [255, 214, 0, 255]);
+            } else if distance < 14.5 {
+                put(&mut image, x, y, line);
+            }
+        }
+    }
+    match mood {
+        Mood::Happy => {
+            for y in 10..14 {
+                for x in [11, 12, 19, 20] {
+                    put(&mut image, x, y, line);
+                }
+            }
+        }
+        Mood::Won => {
+            // サングラスのつると2枚のレンズ
+            for x in 7..25 {
+                put(&mut image, x, 10, line);
+            }
+            for y in 11..14 {
+                for x in (8..15).chain(17..24) {
+                    put(&mut image, x, y, line);
+                }
+            }
+        }
+        Mood::Lost => {
+            for i in 0..5 {
+                for eye in [10, 17] {
+                    put(&mut image, eye + i, 10 + i, line);
+                    put(&mut image, eye + i, 14 - i, line);
+                }
+            }
+        }
+    }
+    // 笑顔は下向きの弧、負けた顔は上向きの弧
+    let (mouth_y, rows) = if mood == Mood::Lost { (28., 0..23) } else { (14., 19..IMAGE_SIZE) };
+    for y in rows {
+        for x in 0..IMAGE_SIZE {
+            let distance = (x as f32 - center).hypot(y as f32 - mouth_y);
+            let inner = if mood == Mood::Lost { 7. } else { 7.5 };
+            if distance >= inner && distance < inner + 1.5 {
+                put(&mut image, x, y, line);
+            }
+        }
+    }
+    image
+}
+
+/// グリフを拡大した白い数字
+fn digit_image(digit: usize) -> Image {
+    const SCALE: u32 = 5;
+    let mut image = canvas();
+    image.sampler = ImageSampler::nearest();
+    let rows = DIGIT_GLYPHS[digit];
+    let left = (IMAGE_SIZE - 3 * SCALE) / 2;
+    let top = (IMAGE_SIZE - 5 * SCALE) / 2;
+    for (gy, row) in rows.iter().enumerate() {
+        for gx in 0..3u32 {
+            if row >> (2 - gx) & 1 == 0 {
+                continue;
+            }
+            for y in 0..SCALE {
+                for x in 0..SCALE {
+                    put(&mut image, left + gx * SCALE + x, top + gy as u32 * SCALE + y, gray(255));
+                }
+            }
+        }
+    }
+    image
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_builtin_image_is_inserted() {
+        let mut images = Assets::<Image>::default();
+        insert_default_visuals(&mut images);
+        for handle in [&COVER_IMAGE, &FLAG_IMAGE, &MINE_IMAGE].into_iter().chain(&DIGIT_IMAGES) {
+            let image = images.get(handle).expect("builtin image");
+            assert_eq!((image.width(), image.height()), (IMAGE_SIZE, IMAGE_SIZE));
+        }
+    }
+
+    #[test]
+    fn digits_look_different() {
+        let digits: Vec<_> = (0..10).map(digit_image).collect();
+        for (i, digit) in digits.iter().enumerate() {
+            for other in &digits[i + 1..] {
+                assert_ne!(digit.data, other.data);
+            }
+        }
+    }
+}
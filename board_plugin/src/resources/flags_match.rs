@@ -0,0 +1,111 @@
+use bevy::prelude::Component;
+
+/// 対戦モードのプレイヤーの数
+pub const PLAYERS: u8 = 2;
+
+/// 対戦モードの手番と得点
+///
+/// 爆弾を見つけると爆弾の数だけ得点になり、もう一度手番が回ってくる。
+/// 爆弾の合計の過半数を取ったプレイヤーが勝つ。対戦モードのボードのエンティティに付ける
+#[derive(Debug, Clone, Component)]
+pub struct FlagsMatch {
+    current: u8,
+    scores: [u32; PLAYERS as usize],
+    bomb_count: u32,
+}
+
+impl FlagsMatch {
+    pub fn new(bomb_count: u32) -> Self {
+        Self {
+            current: 0,
+            scores: [0; PLAYERS as usize],
+            bomb_count,
+        }
+    }
+
+    /// 手番のプレイヤー
+    pub fn current_player(&self) -> u8 {
+        self.current
+    }
+
+    pub fn score(&self, player: u8) -> u32 {
+        self.scores.get(player as usize).copied().unwrap_or(0)
+    }
+
+    /// 手番のプレイヤーが`bombs`個の爆弾を見つけた
+    pub fn claim(&mut self, bombs: u8) {
+        let score = &mut self.scores[self.current as usize];
+        *score = score.saturating_add(bombs as u32);
+    }
+
+    /// 次のプレイヤーに手番を渡す
+    pub fn pass_turn(&mut self) -> u8 {
+        self.current = (self.current + 1) % PLAYERS;
+        self.current
+    }
+
+    /// 誰かが過半数を取ったか、全ての爆弾が見つかったか
+    pub fn is_over(&self) -> bool {
+        let found: u32 = self.scores.iter().sum();
+        found >= self.bomb_count || self.scores.iter().any(|s| *s as u64 * 2 > self.bomb_count as u64)
+    }
+
+    /// 得点が一番多いプレイヤー、同点なら`None`
+    pub fn leader(&self) -> Option<u8> {
+        let best = self.scores.iter().max().copied()?;
+        let mut leaders = (0..PLAYERS).filter(|p| self.scores[*p as usize] == best);
+        let leader = leaders.next();
+        if leaders.next().is_some() {
+            None
+        } else {
+            leader
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn claim_scores_the_current_player_without_passing() {
+        let mut flags_match = FlagsMatch::new(10);
+        flags_match.claim(2);
+        assert_eq!(flags_match.score(0), 2);
+        assert_eq!(flags_match.current_player(), 0);
+        assert_eq!(flags_match.pass_turn(), 1);
+        flags_match.claim(1);
+        assert_eq!(flags_match.score(1), 1);
+        assert_eq!(flags_match.pass_turn(), 0);
+    }
+
+    #[test]
+    fn majority_ends_the_match() {
+        let mut flags_match = FlagsMatch::new(10);
+        flags_match.claim(5);
+        assert!(!flags_match.is_over());
+        flags_match.claim(1);
+        assert!(flags_match.is_over());
+        assert_eq!(flags_match.leader(), Some(0));
+    }
+
+    #[test]
+    fn finding_every_mine_ends_the_match() {
+        let mut flags_match = FlagsMatch::new(4);
+        flags_match.claim(2);
+        flags_match.pass_turn();
+        assert!(!flags_match.is_over());
+        flags_match.claim(2);
+        assert!(flags_match.is_over());
+    }
+
+    #[test]
+    fn tie_has_no_leader() {
+        let mut flags_match = FlagsMatch::new(4);
+        assert_eq!(flags_match.leader(), None);
+        flags_match.claim(2);
+        flags_match.pass_turn();
+        flags_match.claim(2);
+        assert_eq!(flags_match.leader(), None);
+    }
+}
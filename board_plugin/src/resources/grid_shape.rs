@@ -0,0 +1,192 @@
+use std::sync::Arc;
+use bevy::prelude::Vec2;
+use serde::{Deserialize, Serialize};
+use crate::components::Coordinates;
+use crate::resources::topology::{Hex6, Neighborhood, Topology};
+
+/// 六角形の高さと幅の比
+const HEX_ROW_RATIO: f32 = 0.866_025_4;
+
+/// ボードのマスの形
+///
+/// 六角形のマスは尖った頂点が上を向いた配置で、`Coordinates`を
+/// 軸座標`(q, r)`として扱う。`tile_size`は六角形の幅になる
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum GridShape {
+    #[default]
+    Square,
+    Hexagonal,
+}
+
+impl GridShape {
+    /// マスの形に合わせた隣接関係、四角形のマスでは`neighborhood`を使う
+    pub fn topology(&self, neighborhood: &Neighborhood) -> Arc<dyn Topology> {
+        match self {
+            GridShape::Square => neighborhood.topology(),
+            GridShape::Hexagonal => Arc::new(Hex6),
+        }
+    }
+
+    /// ボード全体の大きさ
+    pub fn board_size(&self, (width, height): (u16, u16), tile_size: f32) -> Vec2 {
+        match self {
+            GridShape::Square => Vec2::new(width as f32, height as f32) * tile_size,
+            GridShape::Hexagonal => {
+                let rows = height.saturating_sub(1) as f32;
+                Vec2::new(
+                    (width as f32 + rows / 2.) * tile_size,
+                    (rows * HEX_ROW_RATIO + 1.) * tile_size,
+                )
+            }
+        }
+    }
+
+    /// ボードの左下からのマスの中心の位置
+    pub fn tile_center(&self, coordinates: Coordinates, tile_size: f32) -> Vec2 {
+        let (x, y) = (coordinates.x as f32, coordinates.y as f32);
+        match self {
+            GridShape::Square => Vec2::new(x + 0.5, y + 0.5) * tile_size,
+            GridShape::Hexagonal => {
+                Vec2::new(x + y / 2. + 0.5, y * HEX_ROW_RATIO + 0.5) * tile_size
+            }
+        }
+    }
+
+    /// 回り込んだ先のマスの中心の位置
+    ///
+    /// `offset`はボードの大きさを単位としたずれで、`(-1, 0)`ならボードの左側に並ぶ
+    pub fn wrapped_tile_center(
+        &self,
+        coordinates: Coordinates,
+        offset: (i8, i8),
+        (width, height): (u16, u16),
+        tile_size: f32,
+    ) -> Vec2 {
+        let origin = self.tile_center(Coordinates::default(), tile_size);
+        let step_x = self.tile_center(Coordinates { x: 1, y: 0 }, tile_size) - origin;
+        let step_y = self.tile_center(Coordinates { x: 0, y: 1 }, tile_size) - origin;
+        self.tile_center(coordinates, tile_size)
+            + step_x * (offset.0 as f32 * width as f32)
+            + step_y * (offset.1 as f32 * height as f32)
+    }
+
+    /// マスのスプライトの大きさ
+    ///
+    /// 六角形のマスは行の間隔に合わせた長方形で、レンガのように並ぶ
+    pub fn tile_sprite_size(&self, tile_size: f32) -> Vec2 {
+        match self {
+            GridShape::Square => Vec2::splat(tile_size),
+            GridShape::Hexagonal => Vec2::new(tile_size, tile_size * HEX_ROW_RATIO),
+        }
+    }
+
+    /// ボードの左下からの位置にあるマス
+    pub fn tile_at(&self, position: Vec2, tile_size: f32, (width, height): (u16, u16)) -> Option<Coordinates> {
+        let (x, y) = match self {
+            GridShape::Square => {
+                let position = position / tile_size;
+                (position.x.floor(), position.y.floor())
+            }
+            GridShape::Hexagonal => {
+                let position = position / tile_size - Vec2::splat(0.5);
+                let r = position.y / HEX_ROW_RATIO;
+                let q = position.x - r / 2.;
+                hex_round(q, r)
+            }
+        };
+        if x < 0. || y < 0. || x >= width as f32 || y >= height as f32 {
+            return None;
+        }
+        Some(Coordinates {
+            x: x as u16,
+            y: y as u16,
+        })
+    }
+}
+
+/// 軸座標を一番近い六角形に丸める
+fn hex_round(q: f32, r: f32) -> (f32, f32) {
+    let s = -q - r;
+    let (mut rq, mut rr, rs) = (q.round(), r.round(), s.round());
+    let (dq, dr, ds) = ((rq - q).abs(), (rr - r).abs(), (rs - s).abs());
+    if dq > dr && dq > ds {
+        rq = -rr - rs;
+    } else if dr > ds {
+        rr = -rq - rs;
+    }
+    (rq, rr)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIZE: f32 = 10.;
+    const MAP: (u16, u16) = (4, 4);
+
+    fn pick(shape: GridShape, x: f32, y: f32) -> Option<(u16, u16)> {
+        shape.tile_at(Vec2::new(x, y), SIZE, MAP).map(|c| (c.x, c.y))
+    }
+
+    #[test]
+    fn hex_round_keeps_whole_coordinates() {
+        assert_eq!(hex_round(0., 0.), (0., 0.));
+        assert_eq!(hex_round(2., 3.), (2., 3.));
+        assert_eq!(hex_round(0.4, 0.), (0., 0.));
+        assert_eq!(hex_round(0.6, 0.), (1., 0.));
+    }
+
+    #[test]
+    fn hex_round_fixes_the_largest_error() {
+        // qとrを別々に丸めると(0, 0)になるが、一番近い六角形は(1, 0)
+        assert_eq!(hex_round(0.45, 0.4), (1., 0.));
+        assert_eq!(hex_round(0.4, 0.45), (0., 1.));
+    }
+
+    #[test]
+    fn square_picks_on_tile_boundaries() {
+        assert_eq!(pick(GridShape::Square, 0., 0.), Some((0, 0)));
+        assert_eq!(pick(GridShape::Square, 9.99, 9.99), Some((0, 0)));
+        assert_eq!(pick(GridShape::Square, 10., 0.), Some((1, 0)));
+        assert_eq!(pick(GridShape::Square, 39.9, 39.9), Some((3, 3)));
+        assert_eq!(pick(GridShape::Square, 40., 0.), None);
+        assert_eq!(pick(GridShape::Square, -0.1, 5.), None);
+    }
+
+    #[test]
+    fn hex_picks_tile_centers() {
+        let shape = GridShape::Hexagonal;
+        for y in 0..MAP.1 {
+            for x in 0..MAP.0 {
+                let coordinates = Coordinates { x, y };
+                let center = shape.tile_center(coordinates, SIZE);
+                assert_eq!(shape.tile_at(center, SIZE, MAP), Some(coordinates));
+            }
+        }
+    }
+
+    #[test]
+    fn hex_picks_on_tile_boundaries() {
+        let shape = GridShape::Hexagonal;
+        let center = shape.tile_center(Coordinates { x: 1, y: 1 }, SIZE);
+        let at = |dx: f32, dy: f32| pick(shape, center.x + dx * SIZE, center.y + dy * SIZE);
+        // 左右の辺は中心から幅の半分
+        assert_eq!(at(0.49, 0.), Some((1, 1)));
+        assert_eq!(at(0.51, 0.), Some((2, 1)));
+        assert_eq!(at(-0.51, 0.), Some((0, 1)));
+        // 右上の辺を越えると右上のマス
+        assert_eq!(at(0.1, 0.5), Some((1, 1)));
+        assert_eq!(at(0.1, 0.6), Some((1, 2)));
+        assert_eq!(at(-0.1, -0.6), Some((1, 0)));
+    }
+
+    #[test]
+    fn hex_outside_the_board_is_none() {
+        let shape = GridShape::Hexagonal;
+        let origin = shape.tile_center(Coordinates::default(), SIZE);
+        assert_eq!(pick(shape, origin.x - 0.6 * SIZE, origin.y), None);
+        assert_eq!(pick(shape, origin.x, origin.y - 0.7 * SIZE), None);
+        let last = shape.tile_center(Coordinates { x: 3, y: 3 }, SIZE);
+        assert_eq!(pick(shape, last.x + 0.6 * SIZE, last.y), None);
+    }
+}
@@ -0,0 +1,112 @@
+use std::path::Path;
+use bevy::prelude::Resource;
+use serde::{Deserialize, Serialize};
+
+/// ハイスコアを保存するファイル
+pub const HIGH_SCORES_PATH: &str = "high_scores.json";
+
+/// 1つの分類に残すハイスコアの数
+const MAX_SCORES: usize = 10;
+
+/// クリアしたゲームの記録
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Score {
+    /// クリアまでの秒数
+    pub seconds: f32,
+    pub map_size: (u16, u16),
+    pub bomb_count: u32,
+    /// クリアまでに失ったライフの数
+    pub lives_lost: u8,
+    /// ゲームを始めたときのライフの数
+    #[serde(default = "default_max_lives")]
+    pub max_lives: u8,
+}
+
+/// ライフの数を持たない古い記録はライフが1つのゲーム
+fn default_max_lives() -> u8 {
+    1
+}
+
+/// ハイスコア、ライフが1つのゲームの記録とライフ制のゲームの記録を分けて持つ
+///
+/// ライフ制のゲームはライフを失わずにクリアしても`lives`に入る
+#[derive(Debug, Clone, Default, Resource, Serialize, Deserialize)]
+pub struct HighScores {
+    pub perfect: Vec<Score>,
+    pub lives: Vec<Score>,
+}
+
+impl HighScores {
+    /// ファイルから読み込む、読めなければ空のハイスコア
+    pub fn load(path: impl AsRef<Path>) -> Self {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)
+    }
+
+    /// 記録を追加して、分類の中での順位を返す、圏外なら`None`
+    pub fn record(&mut self, score: Score) -> Option<usize> {
+        let scores = if score.max_lives <= 1 {
+            &mut self.perfect
+        } else {
+            &mut self.lives
+        };
+        let rank = scores.partition_point(|s| s.seconds <= score.seconds);
+        if rank >= MAX_SCORES {
+            return None;
+        }
+        scores.insert(rank, score);
+        scores.truncate(MAX_SCORES);
+        Some(rank)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn score(seconds: f32, lives_lost: u8, max_lives: u8) -> Score {
+        Score { seconds, map_size: (9, 9), bomb_count: 10, lives_lost, max_lives }
+    }
+
+    #[test]
+    fn single_life_games_go_to_perfect() {
+        let mut high_scores = HighScores::default();
+        assert_eq!(high_scores.record(score(30., 0, 1)), Some(0));
+        assert_eq!(high_scores.perfect.len(), 1);
+        assert!(high_scores.lives.is_empty());
+    }
+
+    #[test]
+    fn lives_games_go_to_lives_even_without_losses() {
+        let mut high_scores = HighScores::default();
+        assert_eq!(high_scores.record(score(30., 0, 3)), Some(0));
+        assert_eq!(high_scores.record(score(20., 2, 3)), Some(0));
+        assert!(high_scores.perfect.is_empty());
+        assert_eq!(high_scores.lives.len(), 2);
+        assert_eq!(high_scores.lives[0].seconds, 20.);
+    }
+
+    #[test]
+    fn slow_scores_fall_off_the_table() {
+        let mut high_scores = HighScores::default();
+        for i in 0..MAX_SCORES {
+            high_scores.record(score(i as f32, 0, 1));
+        }
+        assert_eq!(high_scores.record(score(100., 0, 1)), None);
+        assert_eq!(high_scores.record(score(2.5, 0, 1)), Some(3));
+        assert_eq!(high_scores.perfect.len(), MAX_SCORES);
+    }
+
+    #[test]
+    fn old_scores_without_max_lives_are_single_life() {
+        let json = r#"{"seconds":1.0,"map_size":[9,9],"bomb_count":10,"lives_lost":0}"#;
+        let score: Score = serde_json::from_str(json).unwrap();
+        assert_eq!(score.max_lives, 1);
+    }
+}
@@ -0,0 +1,136 @@
+use std::fmt::Display;
+use serde::{Deserialize, Serialize};
+
+/// 表示する言語
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum Language {
+    #[default]
+    English,
+    Japanese,
+}
+
+/// 英語の文字列表、他の言語で見つからないキーもここから引く
+const ENGLISH: &[(&str, &str)] = &[
+    ("window.title", "Mine Sweeper!"),
+    ("menu.title", "Mine Sweeper"),
+    ("menu.easy", "Easy"),
+    ("menu.normal", "Normal"),
+    ("menu.hard", "Hard"),
+    ("menu.custom", "Custom"),
+    ("menu.daily", "Daily {date} (streak {streak})"),
+    ("menu.flags", "Flags (2 players)"),
+    ("hud.player", "P{player} ({mode})"),
+    ("hud.you_win", "You win!"),
+    ("hud.player_wins", "P{player} wins!"),
+    ("hud.game_over", "Game over"),
+    ("hud.score", "P{player}: {score}"),
+    ("hud.turn", "Turn: P{player}"),
+    ("hud.draw", "Draw"),
+    ("hud.daily", "Daily {date}"),
+    ("hud.streak", "Streak: {streak}"),
+    ("hud.lives", "Lives: {lives}/{max}"),
+    ("hud.won", "Cleared!"),
+    ("hud.paused", "Paused"),
+];
+
+/// 日本語の文字列表
+const JAPANESE: &[(&str, &str)] = &[
+    ("window.title", "マインスイーパー!"),
+    ("menu.title", "マインスイーパー"),
+    ("menu.easy", "かんたん"),
+    ("menu.normal", "ふつう"),
+    ("menu.hard", "むずかしい"),
+    ("menu.custom", "カスタム"),
+    ("menu.daily", "デイリー {date} (連続{streak}日)"),
+    ("menu.flags", "旗取り (2人)"),
+    ("hud.player", "P{player} ({mode})"),
+    ("hud.you_win", "あなたの勝ち!"),
+    ("hud.player_wins", "P{player}の勝ち!"),
+    ("hud.game_over", "ゲーム終了"),
+    ("hud.score", "P{player}: {score}"),
+    ("hud.turn", "手番: P{player}"),
+    ("hud.draw", "引き分け"),
+    ("hud.daily", "デイリー {date}"),
+    ("hud.streak", "連続: {streak}日"),
+    ("hud.lives", "ライフ: {lives}/{max}"),
+    ("hud.won", "クリア!"),
+    ("hud.paused", "一時停止中"),
+];
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::English, Language::Japanese];
+
+    fn table(&self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Language::English => ENGLISH,
+            Language::Japanese => JAPANESE,
+        }
+    }
+
+    /// `key`の文字列、この言語になければ英語、英語にもなければキーをそのまま返す
+    pub fn text(&self, key: &'static str) -> &'static str {
+        lookup(&[self.table(), ENGLISH], key).unwrap_or_else(|| {
+            log::warn!("Missing string {:?} for {:?}", key, self);
+            key
+        })
+    }
+
+    /// `key`の文字列の`{name}`を`args`の値で置き換える
+    pub fn format(&self, key: &'static str, args: &[(&str, &dyn Display)]) -> String {
+        let mut text = self.text(key).to_string();
+        for (name, value) in args {
+            text = text.replace(&format!("{{{}}}", name), &value.to_string());
+        }
+        text
+    }
+
+    /// 次の言語
+    pub fn next(&self) -> Self {
+        let index = Self::ALL.iter().position(|l| l == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+}
+
+/// `tables`を順に探して最初に見つかった文字列
+fn lookup(tables: &[&'static [(&'static str, &'static str)]], key: &str) -> Option<&'static str> {
+    tables
+        .iter()
+        .find_map(|table| table.iter().find(|(k, _)| *k == key).map(|(_, v)| *v))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PARTIAL: &[(&str, &str)] = &[("menu.easy", "partial easy")];
+
+    #[test]
+    fn lookup_falls_back_to_later_tables() {
+        assert_eq!(lookup(&[PARTIAL, ENGLISH], "menu.easy"), Some("partial easy"));
+        assert_eq!(lookup(&[PARTIAL, ENGLISH], "menu.hard"), Some("Hard"));
+        assert_eq!(lookup(&[PARTIAL, ENGLISH], "no.such.key"), None);
+    }
+
+    #[test]
+    fn missing_key_is_returned_as_is() {
+        for language in Language::ALL {
+            assert_eq!(language.text("no.such.key"), "no.such.key");
+        }
+    }
+
+    #[test]
+    fn every_language_has_every_english_key() {
+        for language in Language::ALL {
+            for (key, _) in ENGLISH {
+                assert!(lookup(&[language.table()], key).is_some(), "{:?} lacks {}", language, key);
+            }
+        }
+    }
+
+    #[test]
+    fn format_replaces_placeholders() {
+        let text = Language::Japanese.format("hud.lives", &[("lives", &2), ("max", &3)]);
+        assert_eq!(text, "ライフ: 2/3");
+        assert_eq!(Language::English.format("hud.turn", &[("player", &1)]), "Turn: P1");
+    }
+}
@@ -1,7 +1,18 @@
-pub(crate) mod tile;
-pub(crate) mod tile_map;
+pub mod tile;
+pub mod tile_map;
 pub mod board_options;
-pub(crate) mod board;
+pub mod topology;
+pub mod grid_shape;
+pub mod settings;
+pub mod shape_mask;
+pub mod sound;
+pub mod board;
+pub mod daily;
+pub mod default_visuals;
+pub mod flags_match;
+pub mod high_scores;
+pub mod locale;
+pub mod theme;
 pub(crate) mod board_assets;
 
 
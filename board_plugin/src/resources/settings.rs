@@ -0,0 +1,147 @@
+use std::path::Path;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use crate::resources::locale::Language;
+use crate::resources::BoardAssets;
+
+/// 設定を保存するファイル
+pub const SETTINGS_PATH: &str = "settings.json";
+
+/// 数字の色の組み合わせ
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum Palette {
+    /// テーマの色を使う
+    #[default]
+    Theme,
+    /// 見分けやすい8色
+    Standard,
+    /// 2型2色覚、緑が見えにくい
+    Deuteranopia,
+    /// 1型2色覚、赤が見えにくい
+    Protanopia,
+    /// 3型2色覚、青と黄色が見えにくい
+    Tritanopia,
+}
+
+impl Palette {
+    pub const ALL: [Palette; 5] = [
+        Palette::Theme,
+        Palette::Standard,
+        Palette::Deuteranopia,
+        Palette::Protanopia,
+        Palette::Tritanopia,
+    ];
+
+    /// 1から8の数字の色、`Theme`なら`None`
+    ///
+    /// 色覚の型ごとに見分けやすい色を選び、隣り合う数字が似ないように並べる。
+    /// 1型と2型はOkabe-Itoの配色（1型の6はPaul Tolの水色）、3型はPaul Tolの配色から選ぶ
+    pub fn colors(&self) -> Option<Vec<Color>> {
+        let hex: [&str; 8] = match self {
+            Palette::Theme => return None,
+            Palette::Standard => [
+                "#64B5F6", "#81C784", "#E57373", "#BA68C8", "#FFB74D", "#4DD0E1", "#F5F5F5", "#9E9E9E",
+            ],
+            Palette::Deuteranopia => [
+                "#56B4E9", "#E69F00", "#F5F5F5", "#CC79A7", "#F0E442", "#D55E00", "#009E73", "#9E9E9E",
+            ],
+            Palette::Protanopia => [
+                "#56B4E9", "#F0E442", "#F5F5F5", "#CC79A7", "#E69F00", "#88CCEE", "#009E73", "#9E9E9E",
+            ],
+            Palette::Tritanopia => [
+                "#EE6677", "#66CCEE", "#F5F5F5", "#EE99CC", "#44BB99", "#FFAABB", "#CC3311", "#9E9E9E",
+            ],
+        };
+        Some(
+            hex.iter()
+                .map(|h| Color::from(Srgba::hex(h).unwrap_or(Srgba::WHITE)))
+                .collect(),
+        )
+    }
+
+    /// 次の組み合わせ
+    pub fn next(&self) -> Self {
+        let index = Self::ALL.iter().position(|p| p == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+}
+
+/// 見た目の設定、リソースとして扱う
+///
+/// テーマの`BoardAssets`に重ねて適用する
+#[derive(Debug, Clone, Default, PartialEq, Resource, Serialize, Deserialize)]
+pub struct BoardSettings {
+    #[serde(default)]
+    pub palette: Palette,
+    /// 数字と旗に色以外で見分ける印を付ける
+    #[serde(default)]
+    pub high_contrast: bool,
+    #[serde(default)]
+    pub language: Language,
+}
+
+impl BoardSettings {
+    /// ファイルから読み込む、読めなければ初期設定
+    pub fn load(path: impl AsRef<Path>) -> Self {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)
+    }
+
+    /// テーマから作った`board_assets`に設定を反映する
+    pub fn apply(&self, board_assets: &mut BoardAssets) {
+        if let Some(colors) = self.palette.colors() {
+            board_assets.bomb_counter_colors = colors;
+        }
+        board_assets.high_contrast = self.high_contrast;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn palettes_have_eight_distinct_colors() {
+        assert_eq!(Palette::Theme.colors(), None);
+        for palette in &Palette::ALL[1..] {
+            let colors = palette.colors().unwrap();
+            assert_eq!(colors.len(), 8);
+            for (i, color) in colors.iter().enumerate() {
+                assert!(!colors[i + 1..].contains(color), "{:?} repeats {:?}", palette, color);
+            }
+        }
+    }
+
+    #[test]
+    fn next_cycles_through_every_palette() {
+        let mut palette = Palette::Theme;
+        for expected in Palette::ALL.iter().cycle().skip(1).take(Palette::ALL.len()) {
+            palette = palette.next();
+            assert_eq!(palette, *expected);
+        }
+    }
+
+    #[test]
+    fn apply_keeps_theme_colors_without_a_palette() {
+        let mut board_assets = BoardAssets::default();
+        BoardSettings { high_contrast: true, ..Default::default() }.apply(&mut board_assets);
+        assert_eq!(board_assets.bomb_counter_colors, BoardAssets::default_colors());
+        assert!(board_assets.high_contrast);
+
+        BoardSettings { palette: Palette::Tritanopia, ..Default::default() }.apply(&mut board_assets);
+        assert_eq!(Some(board_assets.bomb_counter_colors), Palette::Tritanopia.colors());
+        assert!(!board_assets.high_contrast);
+    }
+
+    #[test]
+    fn missing_settings_use_defaults() {
+        let settings: BoardSettings = serde_json::from_str("{}").unwrap();
+        assert_eq!(settings, BoardSettings::default());
+    }
+}
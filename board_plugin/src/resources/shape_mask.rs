@@ -0,0 +1,203 @@
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+use fixedbitset::FixedBitSet;
+use serde::{Deserialize, Serialize};
+use crate::components::Coordinates;
+
+/// ボードの形、マスクで存在しないマスを決める
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub enum BoardShape {
+    /// 全てのマスがある長方形
+    #[default]
+    Full,
+    /// マップに内接する円
+    Circle,
+    /// 円から中心をくり抜いた輪、`inner`は外側の半径に対する内側の半径の割合
+    Ring { inner: f32 },
+    /// 手描きの画像、PNGかテキストファイル
+    ///
+    /// PNGは不透明で暗いピクセル、テキストは`#`、`X`、`O`、`1`のマスが存在する。
+    /// 画像の上の行がボードの上の行になり、マップの大きさに合わせて拡大縮小する
+    Bitmap(PathBuf),
+}
+
+impl BoardShape {
+    /// `width`x`height`のマップのマスクを作る、`Full`なら`None`
+    pub fn mask(&self, width: u16, height: u16) -> Result<Option<ShapeMask>, ShapeMaskError> {
+        Ok(match self {
+            BoardShape::Full => None,
+            BoardShape::Circle => Some(ShapeMask::ring(width, height, 0.)),
+            BoardShape::Ring { inner } => Some(ShapeMask::ring(width, height, *inner)),
+            BoardShape::Bitmap(path) => Some(ShapeMask::load(path)?.resized(width, height)),
+        })
+    }
+}
+
+/// マスクの読み込みの失敗
+#[derive(Debug)]
+pub enum ShapeMaskError {
+    Io(std::io::Error),
+    Png(png::DecodingError),
+    /// 拡張子からファイルの形式がわからない
+    UnsupportedFormat(PathBuf),
+    /// 存在するマスがない
+    Empty,
+}
+
+impl Display for ShapeMaskError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ShapeMaskError::Io(e) => write!(f, "failed to read shape mask: {}", e),
+            ShapeMaskError::Png(e) => write!(f, "failed to decode shape mask: {}", e),
+            ShapeMaskError::UnsupportedFormat(path) => {
+                write!(f, "unsupported shape mask format: {}", path.display())
+            }
+            ShapeMaskError::Empty => write!(f, "shape mask has no cells"),
+        }
+    }
+}
+
+impl std::error::Error for ShapeMaskError {}
+
+impl From<std::io::Error> for ShapeMaskError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl From<png::DecodingError> for ShapeMaskError {
+    fn from(value: png::DecodingError) -> Self {
+        Self::Png(value)
+    }
+}
+
+/// 存在するマスを行優先で持つマスク
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShapeMask {
+    width: u16,
+    height: u16,
+    cells: FixedBitSet,
+}
+
+impl ShapeMask {
+    /// 全てのマスが存在するマスク
+    pub fn full(width: u16, height: u16) -> Self {
+        let mut cells = FixedBitSet::with_capacity(width as usize * height as usize);
+        cells.insert_range(..);
+        Self { width, height, cells }
+    }
+
+    /// マップに内接する輪、`inner`が0なら円になる
+    pub fn ring(width: u16, height: u16, inner: f32) -> Self {
+        Self::from_fn(width, height, |x, y| {
+            // マスの中心で判定する
+            let dx = (x as f32 + 0.5) / width as f32 * 2. - 1.;
+            let dy = (y as f32 + 0.5) / height as f32 * 2. - 1.;
+            let distance = dx.hypot(dy);
+            distance <= 1. && distance >= inner
+        })
+    }
+
+    /// テキストから読み込む、最初の行がボードの上の行になる
+    pub fn from_text(text: &str) -> Result<Self, ShapeMaskError> {
+        let lines: Vec<&[u8]> = text
+            .lines()
+            .map(|l| l.trim_end().as_bytes())
+            .collect();
+        let height = lines.len() as u16;
+        let width = lines.iter().map(|l| l.len()).max().unwrap_or(0) as u16;
+        Self::from_fn(width, height, |x, y| {
+            let line = lines[(height - 1 - y) as usize];
+            matches!(line.get(x as usize), Some(b'#' | b'X' | b'x' | b'O' | b'o' | b'1'))
+        })
+        .non_empty()
+    }
+
+    /// PNGから読み込む、不透明で暗いピクセルのマスが存在する
+    pub fn from_png(bytes: &[u8]) -> Result<Self, ShapeMaskError> {
+        let mut decoder = png::Decoder::new(bytes);
+        decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+        let mut reader = decoder.read_info()?;
+        let mut buffer = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buffer)?;
+        let channels = info.color_type.samples();
+        let (width, height) = (info.width as u16, info.height as u16);
+        Self::from_fn(width, height, |x, y| {
+            let row = (height - 1 - y) as usize;
+            let i = (row * info.width as usize + x as usize) * channels;
+            let pixel = &buffer[i..i + channels];
+            let (luminance, alpha) = match pixel {
+                [l] => (*l as u32, 255),
+                [l, a] => (*l as u32, *a),
+                [r, g, b] => ((*r as u32 + *g as u32 + *b as u32) / 3, 255),
+                [r, g, b, a] => ((*r as u32 + *g as u32 + *b as u32) / 3, *a),
+                _ => (255, 0),
+            };
+            alpha >= 128 && luminance < 128
+        })
+        .non_empty()
+    }
+
+    /// 拡張子でPNGかテキストかを判断して読み込む
+    pub fn load(path: &Path) -> Result<Self, ShapeMaskError> {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("png") => Self::from_png(&std::fs::read(path)?),
+            Some("txt") => Self::from_text(&std::fs::read_to_string(path)?),
+            _ => Err(ShapeMaskError::UnsupportedFormat(path.to_path_buf())),
+        }
+    }
+
+    /// 最近傍で`width`x`height`に拡大縮小する
+    pub fn resized(&self, width: u16, height: u16) -> Self {
+        if self.width == width && self.height == height {
+            return self.clone();
+        }
+        Self::from_fn(width, height, |x, y| {
+            let sx = (x as u32 * self.width as u32 / width as u32) as u16;
+            let sy = (y as u32 * self.height as u32 / height as u32) as u16;
+            self.contains(Coordinates { x: sx, y: sy })
+        })
+    }
+
+    /// マスが存在するか、マスクの外は存在しない
+    pub fn contains(&self, coordinates: Coordinates) -> bool {
+        coordinates.x < self.width
+            && coordinates.y < self.height
+            && self
+                .cells
+                .contains(coordinates.y as usize * self.width as usize + coordinates.x as usize)
+    }
+
+    /// 存在するマスの数
+    pub fn count(&self) -> usize {
+        self.cells.count_ones(..)
+    }
+
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    fn from_fn(width: u16, height: u16, f: impl Fn(u16, u16) -> bool) -> Self {
+        let mut cells = FixedBitSet::with_capacity(width as usize * height as usize);
+        for y in 0..height {
+            for x in 0..width {
+                if f(x, y) {
+                    cells.insert(y as usize * width as usize + x as usize);
+                }
+            }
+        }
+        Self { width, height, cells }
+    }
+
+    fn non_empty(self) -> Result<Self, ShapeMaskError> {
+        if self.cells.is_clear() {
+            Err(ShapeMaskError::Empty)
+        } else {
+            Ok(self)
+        }
+    }
+}
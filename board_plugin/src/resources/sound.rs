@@ -0,0 +1,59 @@
+use serde::{Deserialize, Serialize};
+
+/// 続けて開いたときに順に使う音の高さ、ペンタトニックスケールの比率
+const REVEAL_SCALE: [f32; 5] = [1., 1.125, 1.25, 1.5, 1.667];
+
+/// 開いたマスの数が倍になるごとに上げる音の高さ
+const CASCADE_PITCH: f32 = 0.04;
+
+/// ボードで鳴らす音の種類、テーマで種類ごとに音のファイルを決める
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum SoundCue {
+    /// 1つのタイルを開いた
+    Reveal,
+    /// 空のタイルから繋がった範囲をまとめて開いた
+    Cascade,
+    FlagOn,
+    FlagOff,
+    Explosion,
+    Completed,
+}
+
+/// 続けて鳴らした回数`sequence`と開いたマスの数`tiles`から再生速度を決める
+///
+/// 開く音は回数ごとにスケールの次の高さになり、大きな範囲ほど少し高くなるので同じ音が続かない
+pub fn cue_speed(cue: SoundCue, sequence: usize, tiles: usize) -> f32 {
+    match cue {
+        SoundCue::Reveal | SoundCue::Cascade => {
+            let note = REVEAL_SCALE[sequence % REVEAL_SCALE.len()];
+            note * (1. + CASCADE_PITCH * (tiles.max(1) as f32).log2())
+        }
+        _ => 1.,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reveals_walk_up_the_scale_and_wrap() {
+        let speeds: Vec<f32> = (0..6).map(|i| cue_speed(SoundCue::Reveal, i, 1)).collect();
+        assert_eq!(&speeds[..5], &REVEAL_SCALE[..]);
+        assert_eq!(speeds[5], speeds[0]);
+    }
+
+    #[test]
+    fn larger_cascades_sound_higher() {
+        let small = cue_speed(SoundCue::Cascade, 0, 4);
+        let large = cue_speed(SoundCue::Cascade, 0, 64);
+        assert!(1. < small && small < large);
+    }
+
+    #[test]
+    fn other_cues_play_at_normal_speed() {
+        for cue in [SoundCue::FlagOn, SoundCue::FlagOff, SoundCue::Explosion, SoundCue::Completed] {
+            assert_eq!(cue_speed(cue, 3, 100), 1.);
+        }
+    }
+}
@@ -0,0 +1,270 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, AsyncReadExt, LoadContext};
+use bevy::prelude::*;
+use bevy::render::texture::{ImageLoaderSettings, ImageSampler};
+use serde::{Deserialize, Serialize};
+use crate::resources::sound::SoundCue;
+use crate::resources::{BoardAssets, DigitAtlas, SpriteMaterial};
+
+/// 組み込みのテーマのアセットのパス
+pub const BUILTIN_THEMES: [&str; 2] = ["themes/default.theme.json", "themes/dark.theme.json"];
+
+/// `#RRGGBB`か`#RRGGBBAA`で書く色
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct ThemeColor(pub Color);
+
+impl TryFrom<String> for ThemeColor {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Srgba::hex(&value)
+            .map(|c| Self(Color::from(c)))
+            .map_err(|e| format!("invalid color {:?}: {}", value, e))
+    }
+}
+
+impl From<ThemeColor> for String {
+    fn from(value: ThemeColor) -> Self {
+        value.0.to_srgba().to_hex()
+    }
+}
+
+/// 色とテクスチャのパス、テクスチャがなければ色だけ
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThemeMaterial {
+    pub color: ThemeColor,
+    #[serde(default)]
+    pub texture: Option<String>,
+}
+
+impl ThemeMaterial {
+    fn load(&self, asset_server: &AssetServer) -> SpriteMaterial {
+        SpriteMaterial {
+            color: self.color.0,
+            texture: self
+                .texture
+                .as_ref()
+                .map(|path| asset_server.load(path.clone()))
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// 1から8の数字を左上から行ごとに並べた画像
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThemeDigitAtlas {
+    pub texture: String,
+    /// 1つの数字のピクセル数
+    pub tile_size: (u32, u32),
+    pub columns: u32,
+}
+
+impl ThemeDigitAtlas {
+    fn load(&self, asset_server: &AssetServer, layouts: &mut Assets<TextureAtlasLayout>) -> DigitAtlas {
+        let rows = 8_u32.div_ceil(self.columns.max(1));
+        let layout = TextureAtlasLayout::from_grid(
+            UVec2::new(self.tile_size.0, self.tile_size.1),
+            self.columns.max(1),
+            rows,
+            None,
+            None,
+        );
+        DigitAtlas {
+            // 小さく表示してもぼやけないようにする
+            texture: asset_server.load_with_settings(self.texture.clone(), |settings: &mut ImageLoaderSettings| {
+                settings.sampler = ImageSampler::nearest();
+            }),
+            layout: layouts.add(layout),
+        }
+    }
+}
+
+/// データファイルに書くボードの見た目、`BoardAssets`の元になる
+#[derive(Debug, Clone, PartialEq, Asset, TypePath, Serialize, Deserialize)]
+pub struct Theme {
+    pub label: String,
+    pub board: ThemeMaterial,
+    pub tile: ThemeMaterial,
+    pub covered_tile: ThemeMaterial,
+    pub flag: ThemeMaterial,
+    pub bomb: ThemeMaterial,
+    /// 数字のフォントのパス、なければ組み込みの数字
+    #[serde(default)]
+    pub font: Option<String>,
+    /// 周りの爆弾の数ごとの数字の色
+    pub bomb_counter_colors: Vec<ThemeColor>,
+    /// あればフォントの代わりに使う数字のアトラス
+    #[serde(default)]
+    pub digit_atlas: Option<ThemeDigitAtlas>,
+    #[serde(default)]
+    pub player_flag_colors: Vec<ThemeColor>,
+    /// 音の種類ごとの音声ファイルのパス
+    #[serde(default)]
+    pub sounds: HashMap<SoundCue, String>,
+}
+
+impl Theme {
+    /// テクスチャとフォントを読み込んで`BoardAssets`を作る
+    pub fn board_assets(
+        &self,
+        asset_server: &AssetServer,
+        layouts: &mut Assets<TextureAtlasLayout>,
+    ) -> BoardAssets {
+        let colors = |colors: &[ThemeColor]| colors.iter().map(|c| c.0).collect::<Vec<_>>();
+        BoardAssets {
+            label: self.label.clone(),
+            board_material: self.board.load(asset_server),
+            tile_material: self.tile.load(asset_server),
+            covered_tile_material: self.covered_tile.load(asset_server),
+            bomb_counter_font: self
+                .font
+                .as_ref()
+                .map(|path| asset_server.load(path.clone()))
+                .unwrap_or_default(),
+            bomb_counter_colors: colors(&self.bomb_counter_colors),
+            digit_atlas: self
+                .digit_atlas
+                .as_ref()
+                .map(|atlas| atlas.load(asset_server, layouts)),
+            flag_material: self.flag.load(asset_server),
+            bomb_material: self.bomb.load(asset_server),
+            player_flag_colors: if self.player_flag_colors.is_empty() {
+                BoardAssets::default_player_flag_colors()
+            } else {
+                colors(&self.player_flag_colors)
+            },
+            sounds: self.sounds.clone(),
+            high_contrast: false,
+        }
+    }
+}
+
+/// 使うテーマ、リソースとして扱う
+///
+/// `current`を変えるか、今のテーマのファイルが変わると`BoardAssets`を作り直す
+#[derive(Debug, Clone, Default, Resource)]
+pub struct BoardTheme {
+    pub themes: Vec<Handle<Theme>>,
+    pub current: usize,
+}
+
+impl BoardTheme {
+    /// 組み込みのテーマを全て読み込む
+    pub fn builtin(asset_server: &AssetServer) -> Self {
+        Self {
+            themes: BUILTIN_THEMES.iter().map(|path| asset_server.load(*path)).collect(),
+            current: 0,
+        }
+    }
+
+    pub fn handle(&self) -> Option<&Handle<Theme>> {
+        self.themes.get(self.current)
+    }
+
+    /// 次のテーマに切り替える
+    pub fn next(&mut self) {
+        if !self.themes.is_empty() {
+            self.current = (self.current + 1) % self.themes.len();
+        }
+    }
+}
+
+/// テーマの読み込みの失敗
+#[derive(Debug)]
+pub enum ThemeLoaderError {
+    Io(std::io::Error),
+    Json(serde_json::Error),
+}
+
+impl Display for ThemeLoaderError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ThemeLoaderError::Io(e) => write!(f, "failed to read theme: {}", e),
+            ThemeLoaderError::Json(e) => write!(f, "failed to parse theme: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ThemeLoaderError {}
+
+/// `.theme.json`のファイルを`Theme`として読み込む
+#[derive(Debug, Default)]
+pub struct ThemeLoader;
+
+impl AssetLoader for ThemeLoader {
+    type Asset = Theme;
+    type Settings = ();
+    type Error = ThemeLoaderError;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext<'_>,
+    ) -> Result<Theme, ThemeLoaderError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await.map_err(ThemeLoaderError::Io)?;
+        serde_json::from_slice(&bytes).map_err(ThemeLoaderError::Json)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["theme.json"]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn builtin_theme(path: &str) -> Theme {
+        let path = format!("{}/../assets/{}", env!("CARGO_MANIFEST_DIR"), path);
+        let json = std::fs::read_to_string(&path).unwrap();
+        serde_json::from_str(&json).unwrap_or_else(|e| panic!("{}: {}", path, e))
+    }
+
+    #[test]
+    fn colors_round_trip_through_hex() {
+        let color = ThemeColor::try_from("#FF000080".to_string()).unwrap();
+        assert_eq!(String::from(color), "#FF000080");
+        assert!(ThemeColor::try_from("red".to_string()).is_err());
+    }
+
+    #[test]
+    fn builtin_themes_parse() {
+        for path in BUILTIN_THEMES {
+            let theme = builtin_theme(path);
+            assert!(!theme.bomb_counter_colors.is_empty(), "{}", path);
+        }
+    }
+
+    #[test]
+    fn next_theme_wraps_around() {
+        let mut board_theme = BoardTheme {
+            themes: vec![Handle::default(); 2],
+            current: 1,
+        };
+        board_theme.next();
+        assert_eq!(board_theme.current, 0);
+
+        // テーマがなければ何もしない
+        let mut empty = BoardTheme::default();
+        empty.next();
+        assert!(empty.handle().is_none());
+    }
+
+    #[test]
+    fn dark_theme_digit_atlas_fits_its_image() {
+        let atlas = builtin_theme(BUILTIN_THEMES[1]).digit_atlas.expect("digit atlas");
+        let path = format!("{}/../assets/{}", env!("CARGO_MANIFEST_DIR"), atlas.texture);
+        let decoder = png::Decoder::new(std::fs::File::open(&path).unwrap());
+        let reader = decoder.read_info().unwrap();
+        let info = reader.info();
+        // 1から8の数字が全て画像に収まる
+        let rows = 8_u32.div_ceil(atlas.columns);
+        assert!(atlas.columns * atlas.tile_size.0 <= info.width);
+        assert!(rows * atlas.tile_size.1 <= info.height);
+    }
+}
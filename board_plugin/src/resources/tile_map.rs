@@ -1,85 +1,185 @@
-use std::ops::{Deref, DerefMut};
-use rand::{Rng, thread_rng};
+use std::sync::Arc;
+use rand::{Rng, SeedableRng, thread_rng};
+use rand_chacha::ChaCha8Rng;
 use crate::components::Coordinates;
+use crate::resources::shape_mask::ShapeMask;
 use crate::resources::tile::Tile;
+use crate::resources::topology::{Square8, Topology};
 
+/// タイルを行優先で1次元に並べたマップ
 #[derive(Debug, Clone)]
 pub struct TileMap {
-    bomb_count: u16,
+    bomb_count: u32,
+    /// 爆弾が1つ以上あるタイルの数
+    mined_tiles: usize,
+    max_bombs_per_tile: u8,
     height: u16,
     width: u16,
-    map: Vec<Vec<Tile>>,
+    tiles: Vec<Tile>,
+    topology: Arc<dyn Topology>,
+    /// 存在するマス、`None`なら全てのマスが存在する
+    mask: Option<ShapeMask>,
 }
 
 impl TileMap {
     pub fn empty(width: u16, height: u16) -> Self {
-        let map = (0..height)
-            .into_iter()
-            .map(|_| (0..width).into_iter().map(|_| Tile::Empty).collect())
-            .collect();
         Self {
             bomb_count: 0,
+            mined_tiles: 0,
+            max_bombs_per_tile: 1,
             height,
             width,
-            map,
+            tiles: vec![Tile::Empty; width as usize * height as usize],
+            topology: Arc::new(Square8),
+            mask: None,
+        }
+    }
+
+    /// 隣接関係を変更する、爆弾を配置する前に呼ぶ
+    pub fn with_topology(mut self, topology: Arc<dyn Topology>) -> Self {
+        self.topology = topology;
+        self
+    }
+
+    /// 存在しないマスを決めるマスクを設定する、爆弾を配置する前に呼ぶ
+    ///
+    /// マスクの大きさがマップと違う場合は拡大縮小する
+    pub fn with_mask(mut self, mask: Option<ShapeMask>) -> Self {
+        self.mask = mask.map(|m| m.resized(self.width, self.height));
+        self
+    }
+
+    /// マスがマップの内側にあり、マスクで除かれていないか
+    pub fn exists(&self, coordinates: Coordinates) -> bool {
+        self.index(coordinates).is_some()
+            && self.mask.as_ref().is_none_or(|m| m.contains(coordinates))
+    }
+
+    /// `coordinates`の`tiles`でのインデックス、マップの外なら`None`
+    pub fn index(&self, coordinates: Coordinates) -> Option<usize> {
+        if coordinates.x >= self.width || coordinates.y >= self.height {
+            return None;
+        }
+        Some(coordinates.y as usize * self.width as usize + coordinates.x as usize)
+    }
+
+    /// インデックスから座標を計算する
+    pub fn coordinates(&self, index: usize) -> Coordinates {
+        Coordinates {
+            x: (index % self.width as usize) as u16,
+            y: (index / self.width as usize) as u16,
         }
     }
 
-    pub fn set_bombs(&mut self, bomb_count: u16) {
+    /// マスのタイル、存在しないマスなら`None`
+    pub fn get(&self, coordinates: Coordinates) -> Option<Tile> {
+        if !self.exists(coordinates) {
+            return None;
+        }
+        self.index(coordinates).map(|i| self.tiles[i])
+    }
+
+    /// 存在する全てのタイルを座標と一緒に行優先で走査する
+    pub fn iter(&self) -> impl Iterator<Item = (Coordinates, Tile)> + '_ {
+        self.tiles
+            .iter()
+            .enumerate()
+            .map(|(i, tile)| (self.coordinates(i), *tile))
+            .filter(|(coords, _)| self.exists(*coords))
+    }
+
+    /// マスクで除かれたマスを含むタイルの総数
+    pub fn len(&self) -> usize {
+        self.tiles.len()
+    }
+
+    /// 存在するタイルの数
+    pub fn tile_count(&self) -> usize {
+        self.mask.as_ref().map_or(self.tiles.len(), ShapeMask::count)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tiles.is_empty()
+    }
+
+    /// 爆弾を配置する、1つのタイルには`max_per_tile`個まで重ねて置く
+    pub fn set_bombs(&mut self, bomb_count: u32, max_per_tile: u8) {
+        self.set_bombs_with_rng(bomb_count, max_per_tile, &mut thread_rng());
+    }
+
+    /// シードから爆弾を配置する、同じシードとマップなら常に同じ配置になる
+    pub fn set_bombs_seeded(&mut self, bomb_count: u32, max_per_tile: u8, seed: u64) {
+        self.set_bombs_with_rng(bomb_count, max_per_tile, &mut ChaCha8Rng::seed_from_u64(seed));
+    }
+
+    fn set_bombs_with_rng(&mut self, bomb_count: u32, max_per_tile: u8, rng: &mut impl Rng) {
+        let max_per_tile = max_per_tile.max(1);
+        self.max_bombs_per_tile = max_per_tile;
+        // 存在するマスにだけ爆弾を置く
+        let candidates: Vec<usize> = (0..self.tiles.len())
+            .filter(|i| self.exists(self.coordinates(*i)))
+            .collect();
+        let capacity = candidates.len().saturating_mul(max_per_tile as usize);
+        let bomb_count = bomb_count.min(capacity.min(u32::MAX as usize) as u32);
         self.bomb_count = bomb_count;
         let mut  remaining_bombs = bomb_count;
-        let mut rng = thread_rng();
         // Place bombs
         while remaining_bombs > 0 {
-            let (x, y) = (
-                rng.gen_range(0..self.width)as usize,
-                rng.gen_range(0..self.height) as usize,
-                );
-            if let Tile::Empty = self[y][x] {
-                self[y][x] = Tile::Bomb;
-                remaining_bombs -= 1;
+            let index = candidates[rng.gen_range(0..candidates.len())];
+            match self.tiles[index] {
+                Tile::Empty => {
+                    self.tiles[index] = Tile::Bomb(1);
+                    self.mined_tiles += 1;
+                    remaining_bombs -= 1;
+                }
+                Tile::Bomb(v) if v < max_per_tile => {
+                    self.tiles[index] = Tile::Bomb(v + 1);
+                    remaining_bombs -= 1;
+                }
+                _ => {}
             }
         }
         // Place bomb neighbors
-        for y in 0..self.height {
-            for x in 0..self.width {
-                let coords = Coordinates {x,y};
-                if self.is_bomb_at(coords) {
-                    continue;
-                }
-                let num = self.bomb_count_at(coords);
-                if num == 0 {
-                    continue;
-                }
-                let tile = &mut self[y as usize][x as usize];
-                *tile = Tile::BombNeighbor(num);
+        for index in candidates {
+            let coords = self.coordinates(index);
+            if self.is_bomb_at(coords) {
+                continue;
+            }
+            let num = self.bomb_count_at(coords);
+            if num == 0 {
+                continue;
             }
+            self.tiles[index] = Tile::BombNeighbor(num);
         }
     }
 
+    /// 周囲の爆弾の合計の数
     pub fn bomb_count_at(&self, coordinates: Coordinates) -> u8 {
         if self.is_bomb_at(coordinates) {
             return 0;
         }
-        let res = self
-            .safe_square_at(coordinates)
-            .filter(|coord| self.is_bomb_at(*coord))
-            .count();
-        res as u8
+        self.neighbors(coordinates)
+            .map(|coord| self.bombs_at(coord))
+            .fold(0, u8::saturating_add)
+    }
+
+    /// タイルにある爆弾の数
+    pub fn bombs_at(&self, coordinates: Coordinates) -> u8 {
+        match self.get(coordinates) {
+            Some(Tile::Bomb(v)) => v,
+            _ => 0,
+        }
     }
 
     pub fn  is_bomb_at(&self, coordinates: Coordinates) -> bool {
-        if coordinates.x >= self.width || coordinates.y >= self.height {
-            return false;
-        };
-        self.map[coordinates.y as usize][coordinates.x as usize].is_bomb()
+        self.get(coordinates).is_some_and(|tile| tile.is_bomb())
     }
 
-    pub fn safe_square_at(&self, coordinates: Coordinates) -> impl Iterator<Item = Coordinates> {
-        SQUARE_COORDINATES
-            .iter()
-            .copied()
-            .map(move |tuple| coordinates + tuple)
+    /// `coordinates`に隣接する存在するマス
+    pub fn neighbors(&self, coordinates: Coordinates) -> impl Iterator<Item = Coordinates> + '_ {
+        self.topology
+            .neighbors(coordinates, self.width, self.height)
+            .filter(|c| self.exists(*c))
     }
 
     pub fn width(&self) -> u16 {
@@ -98,51 +198,125 @@ impl TileMap {
         );
         let line: String = (0..=(self.width + 1)).into_iter().map(|_| '-').collect();
         buffer = format!("{}{}\n", buffer, line);
-        for line in self.iter().rev() {
+        for y in (0..self.height).rev() {
             buffer = format!("{}|", buffer);
-            for tile in line.iter() {
-                buffer = format!("{}{}", buffer, tile.console_output());
+            for x in 0..self.width {
+                // 存在しないマスは`#`で表示する
+                let tile = match self.get(Coordinates { x, y }) {
+                    Some(tile) => tile.console_output(),
+                    None => "#".to_string(),
+                };
+                buffer = format!("{}{}", buffer, tile);
             }
             buffer = format!("{}|\n", buffer);
         }
         format!("{}{}", buffer, line)
     }
 
-    pub fn bomb_count(&self)-> u16 {
+    pub fn bomb_count(&self)-> u32 {
         self.bomb_count
     }
-}
 
-impl Deref for TileMap {
-    type Target = Vec<Vec<Tile>>;
+    /// 爆弾が1つ以上あるタイルの数
+    pub fn mined_tiles(&self) -> usize {
+        self.mined_tiles
+    }
 
-    fn deref(&self) -> &Self::Target {
-        &self.map
+    /// 1つのタイルに置ける爆弾の最大数
+    pub fn max_bombs_per_tile(&self) -> u8 {
+        self.max_bombs_per_tile
     }
 }
 
-impl DerefMut for TileMap {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.map
+#[cfg(test)]
+mod tests {
+    use crate::resources::topology::Toroidal;
+    use super::*;
+
+    #[test]
+    fn index_and_coordinates_round_trip() {
+        let tile_map = TileMap::empty(4, 3);
+        for index in 0..tile_map.len() {
+            assert_eq!(tile_map.index(tile_map.coordinates(index)), Some(index));
+        }
+        assert_eq!(tile_map.coordinates(5), Coordinates { x: 1, y: 1 });
+    }
+
+    #[test]
+    fn outside_coordinates_have_no_tile() {
+        let tile_map = TileMap::empty(4, 3);
+        assert_eq!(tile_map.index(Coordinates { x: 4, y: 0 }), None);
+        assert_eq!(tile_map.get(Coordinates { x: 0, y: 3 }), None);
+        assert_eq!(tile_map.iter().count(), 12);
+    }
+
+    #[test]
+    fn every_bomb_is_placed() {
+        let mut tile_map = TileMap::empty(6, 6);
+        tile_map.set_bombs(8, 1);
+        assert_eq!(tile_map.iter().filter(|(_, t)| t.is_bomb()).count(), 8);
     }
-}
 
-/// 周囲8方向への差分
-const SQUARE_COORDINATES: [(i8, i8); 8] = [
-    // Bottom left
-    (-1, -1),
-    // Bottom
-    (0, -1),
-    // Bottom right
-    (1, -1),
-    // Left
-    (-1, 0),
-    // Right
-    (1, 0),
-    // Top Left
-    (-1, 1),
-    // Top
-    (0, 1),
-    // Top right
-    (1, 1),
-];
+    #[test]
+    fn toroidal_counts_each_bomb_once() {
+        // 2x2では全ての差分が他の3マスのどれかに回り込む
+        let mut tile_map = TileMap::empty(2, 2).with_topology(Arc::new(Toroidal(Arc::new(Square8))));
+        tile_map.set_bombs_seeded(1, 1, 0);
+        for (coordinates, tile) in tile_map.iter() {
+            if !tile.is_bomb() {
+                assert_eq!(tile, Tile::BombNeighbor(1), "{}", coordinates);
+            }
+        }
+    }
+
+    /// 中心のマスがない3x3のマップ
+    fn holed() -> TileMap {
+        let mask = ShapeMask::from_text("###\n#.#\n###").unwrap();
+        TileMap::empty(3, 3).with_mask(Some(mask))
+    }
+
+    const CENTER: Coordinates = Coordinates { x: 1, y: 1 };
+
+    #[test]
+    fn masked_cells_get_no_bombs() {
+        for seed in 0..32 {
+            let mut tile_map = holed();
+            tile_map.set_bombs_seeded(6, 2, seed);
+            assert_eq!(tile_map.get(CENTER), None);
+            assert_eq!(tile_map.bombs_at(CENTER), 0);
+            assert_eq!(tile_map.iter().count(), 8);
+            let placed: u32 = tile_map.iter().map(|(c, _)| tile_map.bombs_at(c) as u32).sum();
+            assert_eq!(placed, 6);
+        }
+    }
+
+    #[test]
+    fn bomb_count_is_limited_to_existing_cells() {
+        let mut tile_map = holed();
+        tile_map.set_bombs_seeded(20, 1, 0);
+        assert_eq!(tile_map.bomb_count(), 8);
+        assert_eq!(tile_map.mined_tiles(), 8);
+        assert!(!tile_map.is_bomb_at(CENTER));
+    }
+
+    #[test]
+    fn masked_cells_are_not_neighbors() {
+        let tile_map = holed();
+        assert!(tile_map.neighbors(Coordinates { x: 0, y: 0 }).all(|c| c != CENTER));
+        assert_eq!(tile_map.neighbors(Coordinates { x: 0, y: 0 }).count(), 2);
+        assert_eq!(tile_map.neighbors(Coordinates { x: 1, y: 0 }).count(), 4);
+    }
+
+    #[test]
+    fn neighbor_counts_skip_masked_cells() {
+        for seed in 0..32 {
+            // 8マスのうち7マスが爆弾で、残りの1マスの数は存在する隣接マスの数になる
+            let mut tile_map = holed();
+            tile_map.set_bombs_seeded(7, 1, seed);
+            let (coordinates, tile) = tile_map.iter().find(|(_, t)| !t.is_bomb()).unwrap();
+            let corner = coordinates.x != 1 && coordinates.y != 1;
+            let expected = if corner { 2 } else { 4 };
+            assert_eq!(tile, Tile::BombNeighbor(expected), "{}", coordinates);
+        }
+    }
+}
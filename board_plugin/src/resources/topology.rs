@@ -0,0 +1,289 @@
+use std::fmt::Debug;
+use std::sync::Arc;
+use serde::{Deserialize, Serialize};
+use crate::components::Coordinates;
+
+/// マスの隣接関係
+///
+/// 爆弾の数の計算、空のタイルの連鎖、コードはすべてこれを通して隣接マスを求める
+pub trait Topology: Debug + Send + Sync {
+    /// 隣接するマスへの差分
+    fn offsets(&self) -> &[(i8, i8)];
+
+    /// `coordinates`に隣接するマスのうち、`width`x`height`のマップの内側にあるものを返す
+    fn neighbors(&self, coordinates: Coordinates, width: u16, height: u16) -> Neighbors<'_> {
+        Neighbors::new(self.offsets(), coordinates, width, height, false)
+    }
+}
+
+/// 差分の一覧から隣接マスを順に求めるイテレータ、タイルごとに呼ばれるのでヒープを使わない
+#[derive(Debug, Clone)]
+pub struct Neighbors<'a> {
+    offsets: &'a [(i8, i8)],
+    next: usize,
+    coordinates: Coordinates,
+    width: u16,
+    height: u16,
+    /// 端を反対側の端に回り込ませる
+    wrap: bool,
+}
+
+impl<'a> Neighbors<'a> {
+    pub fn new(offsets: &'a [(i8, i8)], coordinates: Coordinates, width: u16, height: u16, wrap: bool) -> Self {
+        Self { offsets, next: 0, coordinates, width, height, wrap }
+    }
+
+    /// `offset`だけ離れたマス、回り込まなければマップの外は`None`
+    fn target(&self, (x, y): (i8, i8)) -> Option<Coordinates> {
+        if self.wrap {
+            Some(Coordinates {
+                x: (self.coordinates.x as i32 + x as i32).rem_euclid(self.width as i32) as u16,
+                y: (self.coordinates.y as i32 + y as i32).rem_euclid(self.height as i32) as u16,
+            })
+        } else {
+            self.coordinates
+                .checked_offset((x, y))
+                .filter(|c| c.x < self.width && c.y < self.height)
+        }
+    }
+}
+
+impl Iterator for Neighbors<'_> {
+    type Item = Coordinates;
+
+    fn next(&mut self) -> Option<Coordinates> {
+        while let Some(offset) = self.offsets.get(self.next) {
+            let index = self.next;
+            self.next += 1;
+            let Some(target) = self.target(*offset) else {
+                continue;
+            };
+            // 小さなマップでは回り込んだ先が自分自身や、前の差分と同じマスになる
+            if self.wrap
+                && (target == self.coordinates
+                    || self.offsets[..index].iter().any(|o| self.target(*o) == Some(target)))
+            {
+                continue;
+            }
+            return Some(target);
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.offsets.len() - self.next))
+    }
+}
+
+/// 周囲8方向への差分
+const SQUARE_COORDINATES: [(i8, i8); 8] = [
+    // Bottom left
+    (-1, -1),
+    // Bottom
+    (0, -1),
+    // Bottom right
+    (1, -1),
+    // Left
+    (-1, 0),
+    // Right
+    (1, 0),
+    // Top Left
+    (-1, 1),
+    // Top
+    (0, 1),
+    // Top right
+    (1, 1),
+];
+
+/// 上下左右の4方向への差分
+const ORTHOGONAL_COORDINATES: [(i8, i8); 4] = [
+    // Bottom
+    (0, -1),
+    // Left
+    (-1, 0),
+    // Right
+    (1, 0),
+    // Top
+    (0, 1),
+];
+
+/// 六角形のマスの軸座標での6方向への差分
+const HEX_COORDINATES: [(i8, i8); 6] = [
+    // Bottom left
+    (0, -1),
+    // Bottom right
+    (1, -1),
+    // Left
+    (-1, 0),
+    // Right
+    (1, 0),
+    // Top left
+    (-1, 1),
+    // Top right
+    (0, 1),
+];
+
+/// 周囲8マス
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Square8;
+
+impl Topology for Square8 {
+    fn offsets(&self) -> &[(i8, i8)] {
+        &SQUARE_COORDINATES
+    }
+}
+
+/// 上下左右の4マス
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Orthogonal4;
+
+impl Topology for Orthogonal4 {
+    fn offsets(&self) -> &[(i8, i8)] {
+        &ORTHOGONAL_COORDINATES
+    }
+}
+
+/// 六角形のマスの周囲6マス
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Hex6;
+
+impl Topology for Hex6 {
+    fn offsets(&self) -> &[(i8, i8)] {
+        &HEX_COORDINATES
+    }
+}
+
+/// 任意の差分の一覧で決まる隣接マス
+#[derive(Debug, Clone)]
+pub struct Kernel(pub Vec<(i8, i8)>);
+
+impl Topology for Kernel {
+    fn offsets(&self) -> &[(i8, i8)] {
+        &self.0
+    }
+}
+
+/// 端が反対側の端と繋がった隣接関係
+#[derive(Debug, Clone)]
+pub struct Toroidal(pub Arc<dyn Topology>);
+
+impl Topology for Toroidal {
+    fn offsets(&self) -> &[(i8, i8)] {
+        self.0.offsets()
+    }
+
+    fn neighbors(&self, coordinates: Coordinates, width: u16, height: u16) -> Neighbors<'_> {
+        Neighbors::new(self.offsets(), coordinates, width, height, true)
+    }
+}
+
+/// ボード生成オプションで選ぶ隣接関係
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub enum Neighborhood {
+    #[default]
+    Square,
+    Orthogonal,
+    Custom(Vec<(i8, i8)>),
+}
+
+impl Neighborhood {
+    pub fn topology(&self) -> Arc<dyn Topology> {
+        match self {
+            Neighborhood::Square => Arc::new(Square8),
+            Neighborhood::Orthogonal => Arc::new(Orthogonal4),
+            Neighborhood::Custom(offsets) => Arc::new(Kernel(offsets.clone())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn neighbors(topology: &dyn Topology, (x, y): (u16, u16), size: (u16, u16)) -> Vec<(u16, u16)> {
+        let mut neighbors: Vec<_> = topology
+            .neighbors(Coordinates { x, y }, size.0, size.1)
+            .map(|c| (c.x, c.y))
+            .collect();
+        neighbors.sort();
+        neighbors
+    }
+
+    #[test]
+    fn square_corners_stay_inside() {
+        assert_eq!(neighbors(&Square8, (0, 0), (3, 3)), vec![(0, 1), (1, 0), (1, 1)]);
+        assert_eq!(neighbors(&Square8, (2, 2), (3, 3)), vec![(1, 1), (1, 2), (2, 1)]);
+        assert_eq!(neighbors(&Square8, (2, 0), (3, 3)), vec![(1, 0), (1, 1), (2, 1)]);
+    }
+
+    #[test]
+    fn square_edges_and_center() {
+        assert_eq!(
+            neighbors(&Square8, (1, 0), (3, 3)),
+            vec![(0, 0), (0, 1), (1, 1), (2, 0), (2, 1)]
+        );
+        assert_eq!(neighbors(&Square8, (0, 1), (3, 3)).len(), 5);
+        assert_eq!(neighbors(&Square8, (1, 1), (3, 3)).len(), 8);
+    }
+
+    #[test]
+    fn orthogonal_and_hex_corners() {
+        assert_eq!(neighbors(&Orthogonal4, (0, 0), (3, 3)), vec![(0, 1), (1, 0)]);
+        assert_eq!(neighbors(&Hex6, (0, 0), (3, 3)), vec![(0, 1), (1, 0)]);
+        assert_eq!(neighbors(&Hex6, (2, 2), (3, 3)), vec![(1, 2), (2, 1)]);
+    }
+
+    #[test]
+    fn kernel_skips_offsets_outside() {
+        let knight = Kernel(vec![(1, 2), (2, 1), (-1, 2), (-2, -1)]);
+        assert_eq!(neighbors(&knight, (0, 0), (3, 3)), vec![(1, 2), (2, 1)]);
+    }
+
+    #[test]
+    fn single_tile_has_no_neighbors() {
+        assert!(neighbors(&Square8, (0, 0), (1, 1)).is_empty());
+        assert!(neighbors(&Hex6, (0, 0), (1, 1)).is_empty());
+    }
+
+    fn toroidal(topology: impl Topology + 'static) -> Toroidal {
+        Toroidal(Arc::new(topology))
+    }
+
+    #[test]
+    fn toroidal_wraps_corners() {
+        assert_eq!(
+            neighbors(&toroidal(Square8), (0, 0), (3, 3)),
+            vec![(0, 1), (0, 2), (1, 0), (1, 1), (1, 2), (2, 0), (2, 1), (2, 2)]
+        );
+        assert_eq!(
+            neighbors(&toroidal(Orthogonal4), (3, 0), (4, 3)),
+            vec![(0, 0), (2, 0), (3, 1), (3, 2)]
+        );
+    }
+
+    #[test]
+    fn toroidal_dedups_on_a_single_row() {
+        // 幅1では左右の差分が自分自身か上下と同じマスに回り込む
+        assert_eq!(neighbors(&toroidal(Square8), (0, 2), (1, 5)), vec![(0, 1), (0, 3)]);
+        assert_eq!(neighbors(&toroidal(Square8), (0, 0), (1, 5)), vec![(0, 1), (0, 4)]);
+        assert_eq!(neighbors(&toroidal(Orthogonal4), (1, 0), (4, 1)), vec![(0, 0), (2, 0)]);
+        // 長さ2では前後が同じマスになる
+        assert_eq!(neighbors(&toroidal(Square8), (0, 0), (1, 2)), vec![(0, 1)]);
+    }
+
+    #[test]
+    fn toroidal_dedups_on_two_by_two() {
+        for (x, y) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+            let found = neighbors(&toroidal(Square8), (x, y), (2, 2));
+            assert_eq!(found.len(), 3);
+            assert!(!found.contains(&(x, y)));
+        }
+        assert_eq!(neighbors(&toroidal(Orthogonal4), (0, 0), (2, 2)), vec![(0, 1), (1, 0)]);
+        assert_eq!(neighbors(&toroidal(Hex6), (0, 0), (2, 2)), vec![(0, 1), (1, 0), (1, 1)]);
+    }
+
+    #[test]
+    fn toroidal_single_tile_has_no_neighbors() {
+        assert!(neighbors(&toroidal(Square8), (0, 0), (1, 1)).is_empty());
+    }
+}
@@ -0,0 +1,39 @@
+use bevy::prelude::*;
+
+/// `BoardPlugin::running_state`の間だけ`Active`になる
+///
+/// プラグインは利用者の状態の型を知らないので、`GamePhase`の親としてこの状態を挟む
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Hash, States)]
+pub enum BoardState {
+    #[default]
+    Inactive,
+    Active,
+}
+
+/// メインのボードの1回のゲームの進み具合
+///
+/// ボードを生成すると`NotStarted`から始まり、最初にタイルを開くと`Playing`になる。
+/// `Won`と`Lost`には1回のゲームで1度だけ移り、そこから戻らない
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Hash, SubStates)]
+#[source(BoardState = BoardState::Active)]
+pub enum GamePhase {
+    #[default]
+    NotStarted,
+    Playing,
+    /// 仮想時間を止めて、入力もタイマーも止める
+    Paused,
+    Won,
+    Lost,
+}
+
+impl GamePhase {
+    /// まだ終わっていない
+    pub fn is_running(&self) -> bool {
+        matches!(self, GamePhase::NotStarted | GamePhase::Playing)
+    }
+}
+
+/// ゲームが終わっておらず一時停止もしていない
+pub fn game_running(phase: Option<Res<State<GamePhase>>>) -> bool {
+    phase.is_some_and(|p| p.is_running())
+}
@@ -0,0 +1,237 @@
+use std::f32::consts::TAU;
+use bevy::prelude::*;
+use bevy::utils::{HashMap, HashSet};
+use crate::components::{Coordinates, CoverReveal, Debris, PopIn};
+use crate::events::BombExplosionEvent;
+use crate::resources::board::Board;
+use crate::resources::BoardAssets;
+
+/// カバーが縮んで消えるまでの秒数
+const COVER_DURATION: f32 = 0.15;
+/// 開いた範囲の1マス隣に波が進むまでの秒数
+const WAVE_STEP: f32 = 0.025;
+/// 波の最後のカバーが縮み始めるまでの最大の秒数
+const WAVE_MAX_DELAY: f32 = 0.6;
+/// 旗が元の大きさになるまでの秒数
+const POP_DURATION: f32 = 0.2;
+/// 爆発の破片の数
+const DEBRIS_COUNT: usize = 12;
+/// 破片が消えるまでの秒数
+const DEBRIS_DURATION: f32 = 0.6;
+/// 揺れが収まるまでの秒数
+const SHAKE_DURATION: f32 = 0.35;
+/// タイルの大きさに対する揺れの大きさ
+const SHAKE_STRENGTH: f32 = 0.3;
+
+/// 開いたタイルのカバーを、クリックしたタイルからの距離の順に縮めて消す
+///
+/// `uncovered`は`Board::uncover_region`の幅優先の順に並んでいるので、先に開いた隣のマスから距離が決まる。
+/// 開いた隣のマスがなければ新しい範囲の始まりとして距離を0にする
+pub(crate) fn reveal_wave(
+    commands: &mut Commands,
+    board: &Board,
+    uncovered: &[(Coordinates, Entity)],
+) {
+    let mut distances: HashMap<Coordinates, u32> = HashMap::with_capacity(uncovered.len());
+    for (coords, _) in uncovered {
+        let distance = board
+            .tile_map
+            .neighbors(*coords)
+            .filter_map(|c| distances.get(&c))
+            .min()
+            .map_or(0, |d| d + 1);
+        distances.insert(*coords, distance);
+    }
+    let farthest = distances.values().copied().max().unwrap_or(0).max(1);
+    let step = WAVE_STEP.min(WAVE_MAX_DELAY / farthest as f32);
+    for (coords, cover) in uncovered {
+        commands.entity(*cover).insert(CoverReveal {
+            delay: distances[coords] as f32 * step,
+            elapsed: 0.,
+        });
+    }
+}
+
+/// 爆発したタイルから破片を飛ばす
+pub(crate) fn spawn_explosion(
+    commands: &mut Commands,
+    board: &Board,
+    board_assets: &BoardAssets,
+    coords: Coordinates,
+) {
+    let center = board.grid_shape.tile_center(coords, board.tile_size);
+    let size = board.tile_size / 5.;
+    commands.entity(board.entity).with_children(|parent| {
+        for i in 0..DEBRIS_COUNT {
+            let angle = i as f32 / DEBRIS_COUNT as f32 * TAU;
+            // 1つおきに速さを変えて輪に見えないようにする
+            let speed = board.tile_size * if i % 2 == 0 { 4. } else { 2.5 };
+            let color = if i % 3 == 0 {
+                board_assets.bomb_material.color
+            } else {
+                board_assets.flag_material.color
+            };
+            parent
+                .spawn(SpriteBundle {
+                    sprite: Sprite {
+                        color,
+                        custom_size: Some(Vec2::splat(size)),
+                        ..Default::default()
+                    },
+                    transform: Transform::from_translation(center.extend(5.)),
+                    ..Default::default()
+                })
+                .insert(Name::new("Debris"))
+                .insert(Debris {
+                    velocity: Vec2::from_angle(angle) * speed,
+                    elapsed: 0.,
+                });
+        }
+    });
+}
+
+pub fn animate_covers(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut covers: Query<(Entity, &mut CoverReveal, &mut Transform, &mut Sprite)>,
+) {
+    for (entity, mut reveal, mut transform, mut sprite) in covers.iter_mut() {
+        reveal.elapsed += time.delta_seconds();
+        let t = ((reveal.elapsed - reveal.delay) / COVER_DURATION).clamp(0., 1.);
+        if t >= 1. {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+        transform.scale = Vec3::splat(1. - t);
+        sprite.color.set_alpha(1. - t);
+    }
+}
+
+pub fn animate_flags(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut flags: Query<(Entity, &mut PopIn, &mut Transform)>,
+) {
+    for (entity, mut pop, mut transform) in flags.iter_mut() {
+        pop.elapsed += time.delta_seconds();
+        let t = (pop.elapsed / POP_DURATION).min(1.);
+        // 0から1.2まで大きくなってから1に戻る
+        let scale = if t < 0.7 { t / 0.7 * 1.2 } else { 1.2 - (t - 0.7) / 0.3 * 0.2 };
+        transform.scale = Vec3::splat(scale);
+        if t >= 1. {
+            commands.entity(entity).remove::<PopIn>();
+        }
+    }
+}
+
+pub fn animate_debris(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut debris: Query<(Entity, &mut Debris, &mut Transform, &mut Sprite)>,
+) {
+    for (entity, mut piece, mut transform, mut sprite) in debris.iter_mut() {
+        piece.elapsed += time.delta_seconds();
+        let t = piece.elapsed / DEBRIS_DURATION;
+        if t >= 1. {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+        transform.translation += (piece.velocity * (1. - t) * time.delta_seconds()).extend(0.);
+        transform.scale = Vec3::splat(1. - t);
+        sprite.color.set_alpha(1. - t);
+    }
+}
+
+/// 揺れの残り時間とボードをずらしている量
+#[derive(Debug, Default)]
+pub struct ShakeState {
+    remaining: f32,
+    offset: Vec2,
+}
+
+/// 爆発したボードを揺らす
+pub fn shake_board(
+    time: Res<Time>,
+    mut bomb_explosion_evr: EventReader<BombExplosionEvent>,
+    mut states: Local<HashMap<Entity, ShakeState>>,
+    mut boards: Query<(Entity, &Board, &mut Transform)>,
+) {
+    let exploded: HashSet<Entity> = bomb_explosion_evr.read().map(|e| e.board).collect();
+    // なくなったボードの揺れは捨てる
+    states.retain(|entity, _| boards.contains(*entity));
+    for (entity, board, mut transform) in boards.iter_mut() {
+        if exploded.contains(&entity) && board.animations {
+            states.entry(entity).or_default().remaining = SHAKE_DURATION;
+        }
+        let Some(state) = states.get_mut(&entity) else {
+            continue;
+        };
+        transform.translation -= state.offset.extend(0.);
+        state.remaining = (state.remaining - time.delta_seconds()).max(0.);
+        state.offset = if state.remaining > 0. {
+            let strength = board.tile_size * SHAKE_STRENGTH * state.remaining / SHAKE_DURATION;
+            let phase = time.elapsed_seconds() * 60.;
+            Vec2::new(phase.sin(), (phase * 1.3).cos()) * strength
+        } else {
+            Vec2::ZERO
+        };
+        transform.translation += state.offset.extend(0.);
+        if state.remaining <= 0. {
+            states.remove(&entity);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use bevy::ecs::system::RunSystemOnce;
+    use super::*;
+
+    /// 1回の更新で`seconds`秒進むワールド
+    fn world(seconds: f32) -> World {
+        let mut world = World::new();
+        let mut time = Time::<()>::default();
+        time.advance_by(Duration::from_secs_f32(seconds));
+        world.insert_resource(time);
+        world
+    }
+
+    #[test]
+    fn covers_wait_then_shrink_away() {
+        let mut world = world(0.12);
+        let reveal = CoverReveal { delay: 0.05, elapsed: 0. };
+        let cover = world.spawn((reveal, Transform::default(), Sprite::default())).id();
+        world.run_system_once(animate_covers);
+        let scale = world.get::<Transform>(cover).unwrap().scale.x;
+        assert!((scale - (1. - 0.07 / COVER_DURATION)).abs() < 1e-3, "{}", scale);
+        world.run_system_once(animate_covers);
+        assert!(world.get_entity(cover).is_none());
+    }
+
+    #[test]
+    fn flags_overshoot_then_settle() {
+        let mut world = world(POP_DURATION * 0.7);
+        let flag = world.spawn((PopIn::default(), Transform::default())).id();
+        world.run_system_once(animate_flags);
+        let scale = world.get::<Transform>(flag).unwrap().scale.x;
+        assert!((scale - 1.2).abs() < 1e-3, "{}", scale);
+        world.run_system_once(animate_flags);
+        let scale = world.get::<Transform>(flag).unwrap().scale.x;
+        assert!((scale - 1.).abs() < 1e-3, "{}", scale);
+        assert!(world.get::<PopIn>(flag).is_none());
+    }
+
+    #[test]
+    fn debris_flies_out_and_fades() {
+        let mut world = world(DEBRIS_DURATION * 0.6);
+        let piece = Debris { velocity: Vec2::X, elapsed: 0. };
+        let debris = world.spawn((piece, Transform::default(), Sprite::default())).id();
+        world.run_system_once(animate_debris);
+        let transform = world.get::<Transform>(debris).unwrap();
+        assert!(transform.translation.x > 0.);
+        assert!((transform.scale.x - 0.4).abs() < 1e-3);
+        world.run_system_once(animate_debris);
+        assert!(world.get_entity(debris).is_none());
+    }
+}
@@ -0,0 +1,175 @@
+use bevy::prelude::*;
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy::render::texture::ImageSampler;
+use crate::components::{ChunkRedraw, TileChunk};
+use crate::events::{BoardCompletedEvent, FlagChangedEvent, SoundCueEvent, TileMarkEvent};
+use crate::resources::board::Board;
+use crate::resources::board_options::RenderMode;
+use crate::resources::BoardAssets;
+use crate::resources::default_visuals::DIGIT_GLYPHS;
+use crate::resources::tile::Tile;
+use crate::systems::mark::{mark_cue, send_completed};
+
+/// チャンクのテクスチャでの1タイルあたりのピクセル数
+pub const TILE_PIXELS: u32 = 8;
+
+/// 6x6の爆弾のグリフ
+const BOMB_GLYPH: [u8; 6] = [0b011110, 0b111111, 0b111111, 0b111111, 0b111111, 0b011110];
+
+/// 6x6の旗のグリフ
+const FLAG_GLYPH: [u8; 6] = [0b011110, 0b011100, 0b011000, 0b010000, 0b010000, 0b111000];
+
+/// チャンク描画を使っているボードがあるか
+pub fn chunked_rendering(boards: Query<&Board>) -> bool {
+    boards.iter().any(|b| matches!(b.render_mode, RenderMode::Chunked { .. }))
+}
+
+/// チャンクの大きさの透明なテクスチャを生成する、存在しないマスは透明のまま残る
+pub fn chunk_image(chunk: &TileChunk) -> Image {
+    let mut image = Image::new_fill(
+        Extent3d {
+            width: chunk.width as u32 * TILE_PIXELS,
+            height: chunk.height as u32 * TILE_PIXELS,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &[0, 0, 0, 0],
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+    image.sampler = ImageSampler::nearest();
+    image
+}
+
+/// チャンクの全タイルをテクスチャに描画する
+pub fn draw_chunk(
+    image: &mut Image,
+    chunk: &TileChunk,
+    board: &Board,
+    board_assets: &BoardAssets,
+) {
+    let row_len = (chunk.width as u32 * TILE_PIXELS) as usize;
+    let board_color = board_assets.board_material.color.to_srgba().to_u8_array();
+    for coords in chunk.coordinates() {
+        let Some(tile) = board.tile_map.get(coords) else {
+            continue;
+        };
+        let covered = board.is_covered(&coords);
+        let background = if covered {
+            board_assets.covered_tile_material.color
+        } else {
+            board_assets.tile_material.color
+        };
+        let glyph = if covered {
+            board
+                .is_marked(&coords)
+                .then_some((&FLAG_GLYPH[..], 6, board_assets.flag_material.color))
+        } else {
+            match tile {
+                Tile::Bomb(_) => match board.mine_owner(&coords) {
+                    // 対戦モードで見つけた爆弾はプレイヤーの色の旗にする
+                    Some(player) => Some((&FLAG_GLYPH[..], 6, board_assets.player_flag_color(player))),
+                    // ライフ制で爆発した爆弾には自動で旗が立っている
+                    None if board.flag_level(&coords) > 0 => {
+                        Some((&FLAG_GLYPH[..], 6, board_assets.flag_material.color))
+                    }
+                    None => Some((&BOMB_GLYPH[..], 6, board_assets.bomb_material.color)),
+                },
+                Tile::BombNeighbor(v) => DIGIT_GLYPHS
+                    .get(v as usize)
+                    .map(|g| (&g[..], 3, board_assets.bomb_counter_color(v))),
+                Tile::Empty => None,
+            }
+        };
+        let background = background.to_srgba().to_u8_array();
+        let glyph = glyph.map(|(rows, width, color): (&[u8], i32, Color)| {
+            (rows, width, color.to_srgba().to_u8_array())
+        });
+
+        // 画像の行は上から下、タイルの座標は下から上
+        let left = ((coords.x - chunk.origin.x) as u32 * TILE_PIXELS) as usize;
+        let top = ((chunk.origin.y + chunk.height - 1 - coords.y) as u32 * TILE_PIXELS) as usize;
+        for py in 0..TILE_PIXELS as usize {
+            for px in 0..TILE_PIXELS as usize {
+                let edge = px == 0 || py == 0 || px == TILE_PIXELS as usize - 1 || py == TILE_PIXELS as usize - 1;
+                let mut color = if chunk.padded && edge {
+                    board_color
+                } else if board_assets.high_contrast && glyph.is_some() {
+                    // 小さいグリフでも読めるように黒地にする、数の印を描く余白はない
+                    [0, 0, 0, 255]
+                } else {
+                    background
+                };
+                if let Some((rows, width, glyph_color)) = glyph {
+                    // グリフをタイルの中央に配置する
+                    let gx = px as i32 - (TILE_PIXELS as i32 - width) / 2;
+                    let gy = py as i32 - (TILE_PIXELS as i32 - rows.len() as i32) / 2;
+                    if gx >= 0 && gy >= 0 && gx < width && (gy as usize) < rows.len() {
+                        let bit = width - 1 - gx;
+                        if rows[gy as usize] >> bit & 1 == 1 {
+                            color = glyph_color;
+                        }
+                    }
+                }
+                let i = ((top + py) * row_len + left + px) * 4;
+                image.data[i..i + 4].copy_from_slice(&color);
+            }
+        }
+    }
+}
+
+/// チャンク描画でのタイルのマーキング
+pub fn mark_chunk_tiles(
+    mut commands: Commands,
+    mut boards: Query<&mut Board>,
+    mut tile_mark_event_rdr: EventReader<TileMarkEvent>,
+    mut flag_changed_ewr: EventWriter<FlagChangedEvent>,
+    mut board_completed_ewr: EventWriter<BoardCompletedEvent>,
+    mut sound_cue_ewr: EventWriter<SoundCueEvent>,
+) {
+    for event in tile_mark_event_rdr.read() {
+        let Ok(mut board) = boards.get_mut(event.board) else {
+            continue;
+        };
+        if !matches!(board.render_mode, RenderMode::Chunked { .. }) {
+            continue;
+        }
+        if board.is_lost() || board.is_completed() {
+            continue;
+        }
+        if let Some((chunk, level)) = board.try_cycle_mark(&event.coordinates) {
+            flag_changed_ewr.send(FlagChangedEvent {
+                board: event.board,
+                coordinates: event.coordinates,
+                level,
+                total: board.flag_total(),
+            });
+            sound_cue_ewr.send(SoundCueEvent::new(event.board, mark_cue(level)));
+            send_completed(&board, event.board, &mut board_completed_ewr, &mut sound_cue_ewr);
+            commands.entity(chunk).insert(ChunkRedraw);
+        }
+    }
+}
+
+/// 変更のあったチャンクのテクスチャだけを描き直す
+pub fn redraw_chunks(
+    mut commands: Commands,
+    boards: Query<&Board>,
+    board_assets: Res<BoardAssets>,
+    mut images: ResMut<Assets<Image>>,
+    chunks: Query<(Entity, &TileChunk, &Handle<Image>, &Parent), With<ChunkRedraw>>,
+) {
+    for (entity, chunk, handle, parent) in chunks.iter() {
+        commands.entity(entity).remove::<ChunkRedraw>();
+        // チャンクはボードのエンティティの子
+        let Ok(board) = boards.get(parent.get()) else {
+            continue;
+        };
+        let Some(image) = images.get_mut(handle) else {
+            log::error!("Missing chunk texture for {:?}", entity);
+            continue;
+        };
+        draw_chunk(image, chunk, board, &board_assets);
+    }
+}
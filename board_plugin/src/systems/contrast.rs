@@ -0,0 +1,51 @@
+use bevy::prelude::*;
+
+/// 数字と旗の下に敷く板の色
+const PLATE_COLOR: Color = Color::srgba(0., 0., 0., 0.8);
+/// 大きさに対する印の点の大きさ
+const PIP_SIZE: f32 = 0.1;
+
+/// 濃い下地の板
+fn spawn_plate(parent: &mut ChildBuilder, size: f32) {
+    parent.spawn(SpriteBundle {
+        sprite: Sprite {
+            color: PLATE_COLOR,
+            custom_size: Some(Vec2::splat(size)),
+            ..Default::default()
+        },
+        transform: Transform::from_xyz(0., 0., -0.05),
+        ..Default::default()
+    });
+}
+
+/// `count`個の白い点を`y`の高さに横に並べる
+fn spawn_pips(parent: &mut ChildBuilder, count: u8, size: f32, y: f32) {
+    let pip = size * PIP_SIZE;
+    let step = pip * 1.2;
+    let left = -(count as f32 - 1.) * step / 2.;
+    for i in 0..count {
+        parent.spawn(SpriteBundle {
+            sprite: Sprite {
+                color: Color::WHITE,
+                custom_size: Some(Vec2::splat(pip)),
+                ..Default::default()
+            },
+            transform: Transform::from_xyz(left + i as f32 * step, y, 0.05),
+            ..Default::default()
+        });
+    }
+}
+
+/// 数字の下地と、色に頼らず数が分かるように数だけ点を付ける
+pub(crate) fn spawn_counter_cue(parent: &mut ChildBuilder, count: u8, size: f32) {
+    spawn_plate(parent, size * 0.9);
+    spawn_pips(parent, count.min(8), size, -size * 0.38);
+}
+
+/// 旗の下地と、対戦モードならプレイヤーの番号の数だけ点を付ける
+pub(crate) fn spawn_flag_cue(parent: &mut ChildBuilder, player: Option<u8>, size: f32) {
+    spawn_plate(parent, size * 0.95);
+    if let Some(player) = player {
+        spawn_pips(parent, player + 1, size, size * 0.4);
+    }
+}
@@ -0,0 +1,90 @@
+use bevy::ecs::system::EntityCommands;
+use bevy::prelude::*;
+use crate::components::BombCounter;
+use crate::resources::default_visuals::DIGIT_IMAGES;
+use crate::resources::BoardAssets;
+use crate::systems::contrast::spawn_counter_cue;
+
+/// 数字の幅に対する次の桁までの間隔
+const DIGIT_ADVANCE: f32 = 0.5;
+
+/// `count`を`size`の大きさで表示する
+pub(crate) fn spawn_bomb_count<'a>(
+    parent: &'a mut ChildBuilder,
+    count: u8,
+    board_assets: &BoardAssets,
+    size: f32,
+    transform: Transform,
+) -> EntityCommands<'a> {
+    let mut cmd = parent.spawn(SpatialBundle::from_transform(transform));
+    cmd.insert(BombCounter { count, size })
+        .with_children(|parent| spawn_digits(parent, count, board_assets, size));
+    cmd
+}
+
+/// 数字の中身を生成する
+///
+/// アトラスがあれば1つのスプライト、なければフォントのテキスト、フォントもなければ組み込みの数字のスプライトで描く。
+/// ハイコントラストなら下地と数の点も付ける
+pub(crate) fn spawn_digits(parent: &mut ChildBuilder, count: u8, board_assets: &BoardAssets, size: f32) {
+    let color = board_assets.bomb_counter_color(count);
+    if board_assets.high_contrast {
+        spawn_counter_cue(parent, count, size);
+    }
+    if let Some(atlas) = board_assets.digit_atlas.as_ref().filter(|_| (1..=8).contains(&count)) {
+        parent.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color,
+                    custom_size: Some(Vec2::splat(size)),
+                    ..Default::default()
+                },
+                texture: atlas.texture.clone(),
+                ..Default::default()
+            },
+            TextureAtlas {
+                layout: atlas.layout.clone(),
+                index: count as usize - 1,
+            },
+        ));
+        return;
+    }
+
+    if board_assets.has_counter_font() {
+        parent.spawn(Text2dBundle {
+            text: Text {
+                sections: vec![TextSection {
+                    value: count.to_string(),
+                    style: TextStyle {
+                        color,
+                        font: board_assets.bomb_counter_font.clone(),
+                        font_size: size,
+                    },
+                }],
+                justify: JustifyText::Center,
+                linebreak_behavior: Default::default(),
+            },
+            ..Default::default()
+        });
+        return;
+    }
+
+    let digits: Vec<usize> = count
+        .to_string()
+        .bytes()
+        .map(|b| (b - b'0') as usize)
+        .collect();
+    let left = -(digits.len() as f32 - 1.) * size * DIGIT_ADVANCE / 2.;
+    for (i, digit) in digits.into_iter().enumerate() {
+        parent.spawn(SpriteBundle {
+            sprite: Sprite {
+                color,
+                custom_size: Some(Vec2::splat(size)),
+                ..Default::default()
+            },
+            texture: DIGIT_IMAGES[digit].clone(),
+            transform: Transform::from_xyz(left + i as f32 * size * DIGIT_ADVANCE, 0., 0.),
+            ..Default::default()
+        });
+    }
+}
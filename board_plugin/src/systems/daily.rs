@@ -0,0 +1,70 @@
+use bevy::prelude::*;
+use crate::components::PrimaryBoard;
+use crate::events::{BoardCompletedEvent, BombExplosionEvent, TileMarkEvent, TileTriggerEvent};
+use crate::resources::board::Board;
+use crate::resources::daily::{
+    DailyChallenge, DailyOutcome, DailyReplay, DailyResult, DailyResults, ReplayAction,
+    ReplayStep, DAILY_RESULTS_PATH,
+};
+
+/// デイリーチャレンジのボードを生成したら記録を始める
+pub fn start_daily_replay(mut commands: Commands) {
+    commands.insert_resource(DailyReplay::default());
+}
+
+/// メインのボードでのプレイヤーの操作をリプレイに記録する、生成時の安全なタイルは記録しない
+pub fn record_daily_replay(
+    time: Res<Time>,
+    primary: Query<(Entity, &Board), With<PrimaryBoard>>,
+    mut replay: ResMut<DailyReplay>,
+    mut tile_trigger_evr: EventReader<TileTriggerEvent>,
+    mut tile_mark_evr: EventReader<TileMarkEvent>,
+) {
+    let Ok((entity, board)) = primary.get_single() else {
+        return;
+    };
+    let seconds = (time.elapsed() - board.started_at()).as_secs_f32();
+    for event in tile_trigger_evr.read().filter(|e| e.board == entity && !e.safe_start) {
+        replay.0.push(ReplayStep { seconds, action: ReplayAction::Uncover(event.coordinates) });
+    }
+    for event in tile_mark_evr.read().filter(|e| e.board == entity) {
+        replay.0.push(ReplayStep { seconds, action: ReplayAction::Mark(event.coordinates) });
+    }
+}
+
+/// メインのボードがクリアかゲームオーバーになったらその日の結果を保存する
+pub fn finish_daily(
+    time: Res<Time>,
+    primary: Query<(Entity, &Board), With<PrimaryBoard>>,
+    challenge: Res<DailyChallenge>,
+    replay: Res<DailyReplay>,
+    mut results: ResMut<DailyResults>,
+    mut board_completed_evr: EventReader<BoardCompletedEvent>,
+    mut bomb_explosion_evr: EventReader<BombExplosionEvent>,
+) {
+    let Ok((entity, board)) = primary.get_single() else {
+        return;
+    };
+    let completed = board_completed_evr.read().any(|e| e.board == entity);
+    let exploded = bomb_explosion_evr.read().any(|e| e.board == entity);
+    let outcome = if completed {
+        DailyOutcome::Won
+    } else if exploded && board.is_lost() {
+        DailyOutcome::Lost
+    } else {
+        return;
+    };
+    let result = DailyResult {
+        seconds: (time.elapsed() - board.started_at()).as_secs_f32(),
+        outcome,
+        replay: replay.0.clone(),
+    };
+    if !results.record(challenge.date, result) {
+        log::info!("Daily challenge {} was already played", challenge.date);
+        return;
+    }
+    log::info!("Daily challenge {}: {:?}", challenge.date, outcome);
+    if let Err(e) = results.save(DAILY_RESULTS_PATH) {
+        log::error!("Failed to save daily results: {}", e);
+    }
+}
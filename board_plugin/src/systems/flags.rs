@@ -0,0 +1,112 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use crate::components::{ChunkRedraw, Coordinates, PopIn, ThemePart};
+use crate::events::{
+    FlagsMatchEndedEvent, MineClaimedEvent, SoundCueEvent, TileTriggerEvent, TilesRevealedEvent, TurnChangedEvent,
+};
+use crate::resources::board::Board;
+use crate::resources::board_options::RenderMode;
+use crate::resources::flags_match::FlagsMatch;
+use crate::resources::sound::SoundCue;
+use crate::resources::BoardAssets;
+use crate::systems::contrast::spawn_flag_cue;
+use crate::systems::uncover::remove_covers;
+
+/// 対戦モードでクリックされたタイルを開く
+///
+/// 爆弾を開くと手番のプレイヤーの色の旗を立てて得点になり、同じプレイヤーがもう一度開く。
+/// 爆弾以外を開くと手番が移る、ただし生成時の安全なタイルでは移らない。対戦モードではコードは使えない
+#[allow(clippy::too_many_arguments)]
+pub fn flags_uncover_tiles(
+    mut commands: Commands,
+    mut boards: Query<(&mut Board, &mut FlagsMatch)>,
+    board_assets: Res<BoardAssets>,
+    mut tile_trigger_evr: EventReader<TileTriggerEvent>,
+    mut mine_claimed_ewr: EventWriter<MineClaimedEvent>,
+    mut turn_changed_ewr: EventWriter<TurnChangedEvent>,
+    mut match_ended_ewr: EventWriter<FlagsMatchEndedEvent>,
+    mut tiles_revealed_ewr: EventWriter<TilesRevealedEvent>,
+    mut sound_cue_ewr: EventWriter<SoundCueEvent>,
+) {
+    let mut uncovered: HashMap<Entity, Vec<(Coordinates, Entity)>> = HashMap::new();
+    for event in tile_trigger_evr.read() {
+        let Ok((mut board, mut flags_match)) = boards.get_mut(event.board) else {
+            continue;
+        };
+        if flags_match.is_over() {
+            continue;
+        }
+        let coordinates = event.coordinates;
+        if board.tile_to_uncover(&coordinates).is_none() {
+            continue;
+        }
+        let region = board.uncover_region(coordinates);
+        if region.is_empty() {
+            continue;
+        }
+        tiles_revealed_ewr.send(TilesRevealedEvent {
+            board: event.board,
+            coordinates,
+            count: region.len(),
+            safe_start: event.safe_start,
+        });
+        if !board.tile_map.is_bomb_at(coordinates) {
+            sound_cue_ewr.send(SoundCueEvent::reveal(event.board, region.len()));
+            uncovered.entry(event.board).or_default().extend(region);
+            if !event.safe_start {
+                turn_changed_ewr.send(TurnChangedEvent { board: event.board, player: flags_match.pass_turn() });
+            }
+            continue;
+        }
+
+        let player = flags_match.current_player();
+        board.reveal_mine(&coordinates, Some(player));
+        flags_match.claim(board.tile_map.bombs_at(coordinates));
+        log::info!("Player {} found a mine on {}", player + 1, coordinates);
+        mine_claimed_ewr.send(MineClaimedEvent { board: event.board, coordinates, player });
+        sound_cue_ewr.send(SoundCueEvent::new(event.board, SoundCue::FlagOn));
+        // 見つけた爆弾はカバーを残してプレイヤーの色の旗を立てる
+        for (_, entity) in region {
+            match board.render_mode {
+                RenderMode::Entities => {
+                    let flag_size = board.grid_shape.tile_sprite_size(board.tile_size).min_element();
+                    let scale = if board.animations { Vec3::ZERO } else { Vec3::ONE };
+                    commands.entity(entity).with_children(|parent| {
+                        let mut flag = parent.spawn(SpriteBundle {
+                            texture: board_assets.flag_material.texture.clone(),
+                            sprite: Sprite {
+                                color: board_assets.player_flag_color(player),
+                                custom_size: Some(Vec2::splat(flag_size)),
+                                ..Default::default()
+                            },
+                            transform: Transform::from_xyz(0., 0., 2.).with_scale(scale),
+                            ..Default::default()
+                        });
+                        flag.insert(Name::new("Flag"))
+                            .insert(ThemePart::PlayerFlag(player));
+                        if board_assets.high_contrast {
+                            flag.with_children(|parent| spawn_flag_cue(parent, Some(player), flag_size));
+                        }
+                        if board.animations {
+                            flag.insert(PopIn::default());
+                        }
+                    });
+                }
+                RenderMode::Chunked { .. } => {
+                    commands.entity(entity).insert(ChunkRedraw);
+                }
+            }
+        }
+        if flags_match.is_over() {
+            let winner = flags_match.leader();
+            log::info!("Flags match over, winner: {:?}", winner.map(|p| p + 1));
+            match_ended_ewr.send(FlagsMatchEndedEvent { board: event.board, winner });
+            sound_cue_ewr.send(SoundCueEvent::new(event.board, SoundCue::Completed));
+        }
+    }
+    for (entity, uncovered) in uncovered {
+        if let Ok((board, _)) = boards.get(entity) {
+            remove_covers(&mut commands, board, uncovered);
+        }
+    }
+}
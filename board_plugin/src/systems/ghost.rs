@@ -0,0 +1,41 @@
+use bevy::prelude::*;
+use crate::components::Ghost;
+use crate::resources::board::Board;
+use crate::resources::BoardAssets;
+
+/// 端の複製の透明度
+pub const GHOST_ALPHA: f32 = 0.5;
+
+/// 端の複製の表示を複製元のタイルの状態に合わせる
+pub fn sync_ghosts(
+    boards: Query<&Board>,
+    board_assets: Res<BoardAssets>,
+    mut ghosts: Query<(&Ghost, &mut Sprite, &Parent)>,
+    mut visibilities: Query<&mut Visibility>,
+) {
+    for (ghost, mut sprite, parent) in ghosts.iter_mut() {
+        // 複製はボードのエンティティの子
+        let Ok(board) = boards.get(parent.get()) else {
+            continue;
+        };
+        let covered = board.is_covered(&ghost.source);
+        let color = if covered {
+            board_assets.covered_tile_material.color
+        } else {
+            board_assets.tile_material.color
+        };
+        sprite.color = color.with_alpha(GHOST_ALPHA);
+        if let Some(content) = ghost.content {
+            if let Ok(mut visibility) = visibilities.get_mut(content) {
+                *visibility = if covered { Visibility::Hidden } else { Visibility::Inherited };
+            }
+        }
+        if let Ok(mut visibility) = visibilities.get_mut(ghost.flag) {
+            *visibility = if board.is_marked(&ghost.source) {
+                Visibility::Inherited
+            } else {
+                Visibility::Hidden
+            };
+        }
+    }
+}
@@ -0,0 +1,248 @@
+use bevy::input::mouse::MouseButtonInput;
+use bevy::input::ButtonState;
+use bevy::prelude::*;
+use bevy::sprite::Anchor;
+use bevy::window::WindowResized;
+use crate::components::{Hud, PrimaryBoard, RestartButton, ThemePart};
+use crate::events::NewBoardEvent;
+use crate::net::client::NetClient;
+use crate::resources::board::Board;
+use crate::resources::daily::{DailyChallenge, DailyResults};
+use crate::resources::default_visuals::{FACE_IMAGE, FACE_LOST_IMAGE, FACE_WON_IMAGE};
+use crate::resources::flags_match::{FlagsMatch, PLAYERS};
+use crate::resources::locale::Language;
+use crate::resources::settings::BoardSettings;
+use crate::states::GamePhase;
+use crate::resources::BoardAssets;
+
+/// HUDの文字の大きさ
+const HUD_FONT_SIZE: f32 = 20.;
+
+/// HUDとウィンドウの端の間隔
+const HUD_MARGIN: f32 = 8.;
+
+/// やり直しのボタンの大きさ
+const RESTART_BUTTON_SIZE: f32 = 32.;
+
+/// ウィンドウの左上の位置
+fn hud_translation(width: f32, height: f32) -> Vec3 {
+    Vec3::new(-width / 2. + HUD_MARGIN, height / 2. - HUD_MARGIN, 10.)
+}
+
+/// ウィンドウの上の中央の位置
+fn restart_button_translation(height: f32) -> Vec3 {
+    Vec3::new(0., height / 2. - HUD_MARGIN - RESTART_BUTTON_SIZE / 2., 10.)
+}
+
+/// ゲームの進み具合に合わせた顔
+fn face_image(phase: Option<GamePhase>) -> Handle<Image> {
+    match phase {
+        Some(GamePhase::Won) => FACE_WON_IMAGE,
+        Some(GamePhase::Lost) => FACE_LOST_IMAGE,
+        _ => FACE_IMAGE,
+    }
+}
+
+/// カーソルがやり直しのボタンの上にあるか
+pub fn over_restart_button(window: &Window, position: Vec2) -> bool {
+    // カーソルの位置は左上が原点で下向き
+    let center = Vec2::new(window.width() / 2., HUD_MARGIN + RESTART_BUTTON_SIZE / 2.);
+    (position - center).abs().max_element() <= RESTART_BUTTON_SIZE / 2.
+}
+
+/// HUDに表示するテキスト、ライフが1つのときはライフを表示しない
+fn hud_text(
+    board: &Board,
+    daily: Option<&DailyChallenge>,
+    results: &DailyResults,
+    flags_match: Option<&FlagsMatch>,
+    net_client: Option<&NetClient>,
+    phase: Option<GamePhase>,
+    language: Language,
+) -> String {
+    let player_wins = |winner: u8| language.format("hud.player_wins", &[("player", &(winner + 1))]);
+    let mut lines = Vec::new();
+    if let Some(client) = net_client {
+        let mode = format!("{:?}", client.mode);
+        lines.push(language.format("hud.player", &[("player", &(client.player + 1)), ("mode", &mode)]));
+        match client.finished {
+            None => {}
+            Some(Some(winner)) if winner == client.player => lines.push(language.text("hud.you_win").to_string()),
+            Some(Some(winner)) => lines.push(player_wins(winner)),
+            Some(None) => lines.push(language.text("hud.game_over").to_string()),
+        }
+    }
+    if let Some(flags_match) = flags_match {
+        let scores: Vec<String> = (0..PLAYERS)
+            .map(|p| language.format("hud.score", &[("player", &(p + 1)), ("score", &flags_match.score(p))]))
+            .collect();
+        lines.push(scores.join("  "));
+        lines.push(match (flags_match.is_over(), flags_match.leader()) {
+            (false, _) => language.format("hud.turn", &[("player", &(flags_match.current_player() + 1))]),
+            (true, Some(winner)) => player_wins(winner),
+            (true, None) => language.text("hud.draw").to_string(),
+        });
+    }
+    if let Some(challenge) = daily {
+        lines.push(language.format("hud.daily", &[("date", &challenge.date)]));
+        lines.push(language.format("hud.streak", &[("streak", &results.streak(challenge.date))]));
+    }
+    if board.max_lives() > 1 {
+        lines.push(language.format("hud.lives", &[("lives", &board.lives()), ("max", &board.max_lives())]));
+    }
+    // 対戦の結果は上で表示している
+    let versus = net_client.is_some() || flags_match.is_some();
+    match phase {
+        Some(GamePhase::Paused) => lines.push(language.text("hud.paused").to_string()),
+        Some(GamePhase::Won) if !versus => lines.push(language.text("hud.won").to_string()),
+        Some(GamePhase::Lost) if !versus => lines.push(language.text("hud.game_over").to_string()),
+        _ => {}
+    }
+    lines.join("\n")
+}
+
+/// メインのボードのHUD
+type PrimaryQuery<'w, 's> = Query<'w, 's, (&'static Board, Option<&'static FlagsMatch>), With<PrimaryBoard>>;
+
+#[allow(clippy::too_many_arguments)]
+pub fn spawn_hud(
+    mut commands: Commands,
+    primary: PrimaryQuery,
+    board_assets: Res<BoardAssets>,
+    daily: Option<Res<DailyChallenge>>,
+    daily_results: Res<DailyResults>,
+    net_client: Option<Res<NetClient>>,
+    settings: Res<BoardSettings>,
+    phase: Option<Res<State<GamePhase>>>,
+    window: Query<&Window>,
+) {
+    let Ok((board, flags_match)) = primary.get_single() else {
+        return;
+    };
+    let window = window.single();
+    let phase = phase.map(|p| *p.get());
+    commands
+        .spawn(Text2dBundle {
+            text: Text::from_section(
+                hud_text(
+                    board,
+                    daily.as_deref(),
+                    &daily_results,
+                    flags_match,
+                    net_client.as_deref(),
+                    phase,
+                    settings.language,
+                ),
+                TextStyle {
+                    color: board_assets.bomb_counter_color(1),
+                    font: board_assets.bomb_counter_font.clone(),
+                    font_size: HUD_FONT_SIZE,
+                },
+            ),
+            text_anchor: Anchor::TopLeft,
+            transform: Transform::from_translation(hud_translation(window.width(), window.height())),
+            ..Default::default()
+        })
+        .insert(Name::new("HUD"))
+        .insert(Hud)
+        .insert(ThemePart::Counter(1));
+    commands
+        .spawn(SpriteBundle {
+            texture: face_image(phase),
+            sprite: Sprite {
+                custom_size: Some(Vec2::splat(RESTART_BUTTON_SIZE)),
+                ..Default::default()
+            },
+            transform: Transform::from_translation(restart_button_translation(window.height())),
+            ..Default::default()
+        })
+        .insert(Name::new("Restart Button"))
+        .insert(RestartButton);
+}
+
+/// ボードの状態、ゲームの進み具合か言語が変わったらHUDのテキストを更新する
+pub fn update_hud(
+    primary: PrimaryQuery,
+    daily: Option<Res<DailyChallenge>>,
+    daily_results: Res<DailyResults>,
+    net_client: Option<Res<NetClient>>,
+    settings: Res<BoardSettings>,
+    phase: Option<Res<State<GamePhase>>>,
+    mut huds: Query<&mut Text, With<Hud>>,
+) {
+    let Ok((board, flags_match)) = primary.get_single() else {
+        return;
+    };
+    for mut text in huds.iter_mut() {
+        if let Some(section) = text.sections.first_mut() {
+            section.value = hud_text(
+                board,
+                daily.as_deref(),
+                &daily_results,
+                flags_match,
+                net_client.as_deref(),
+                phase.as_ref().map(|p| *p.get()),
+                settings.language,
+            );
+        }
+    }
+}
+
+/// ゲームの進み具合が変わったらやり直しのボタンの顔を変える
+pub fn update_restart_button(
+    phase: Option<Res<State<GamePhase>>>,
+    mut buttons: Query<&mut Handle<Image>, With<RestartButton>>,
+) {
+    let image = face_image(phase.map(|p| *p.get()));
+    for mut texture in buttons.iter_mut() {
+        *texture = image.clone();
+    }
+}
+
+/// やり直しのボタンが左クリックされたらメインのボードを新しいボードにする
+pub fn click_restart_button(
+    window: Query<&Window>,
+    buttons: Query<(), With<RestartButton>>,
+    primary: Query<Entity, With<PrimaryBoard>>,
+    mut button_evr: EventReader<MouseButtonInput>,
+    mut new_board_ewr: EventWriter<NewBoardEvent>,
+) {
+    let window = window.single();
+    let Ok(board) = primary.get_single() else {
+        return;
+    };
+    for event in button_evr.read() {
+        if event.button != MouseButton::Left || event.state != ButtonState::Pressed || buttons.is_empty() {
+            continue;
+        }
+        if window.cursor_position().is_some_and(|pos| over_restart_button(window, pos)) {
+            new_board_ewr.send(NewBoardEvent { board, options: None });
+        }
+    }
+}
+
+/// ウィンドウの大きさが変わったらHUDを左上に、やり直しのボタンを上の中央に合わせる
+pub fn position_hud(
+    mut resize_evr: EventReader<WindowResized>,
+    mut huds: Query<&mut Transform, (With<Hud>, Without<RestartButton>)>,
+    mut buttons: Query<&mut Transform, (With<RestartButton>, Without<Hud>)>,
+) {
+    let Some(event) = resize_evr.read().last() else {
+        return;
+    };
+    for mut transform in huds.iter_mut() {
+        transform.translation = hud_translation(event.width, event.height);
+    }
+    for mut transform in buttons.iter_mut() {
+        transform.translation = restart_button_translation(event.height);
+    }
+}
+
+/// HUDとやり直しのボタン
+type HudEntities<'w, 's> = Query<'w, 's, Entity, Or<(With<Hud>, With<RestartButton>)>>;
+
+pub fn despawn_hud(mut commands: Commands, huds: HudEntities) {
+    for entity in huds.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
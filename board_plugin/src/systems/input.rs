@@ -1,38 +1,67 @@
 use bevy::input::ButtonState;
 use bevy::input::mouse::MouseButtonInput;
-use bevy::prelude::{EventReader, EventWriter, MouseButton, Query, Res, Window};
+use bevy::prelude::{Entity, EventReader, EventWriter, Has, MouseButton, Query, ResMut, Window};
+use crate::components::PrimaryBoard;
 use crate::events::{TileMarkEvent, TileTriggerEvent};
+use crate::net::client::NetClient;
+use crate::net::protocol::ClientMessage;
 use crate::resources::board::Board;
+use crate::resources::flags_match::FlagsMatch;
+use crate::systems::hud::over_restart_button;
 
+/// クリックされた位置のボードとタイルを探してイベントにする
+///
+/// 終わったボードへのクリックと、やり直しのボタンへのクリックは無視する
 pub fn input_handling(
     window: Query<&Window>,
-    board: Res<Board>,
+    boards: Query<(Entity, &Board, Option<&FlagsMatch>, Has<PrimaryBoard>)>,
+    mut net_client: Option<ResMut<NetClient>>,
     mut button_evr: EventReader<MouseButtonInput>,
     mut tile_trigger_ewr: EventWriter<TileTriggerEvent>,
     mut tile_mark_event:EventWriter<TileMarkEvent>,
 ) {
     let window = window.single();
-
     for event in button_evr.read() {
         if let ButtonState::Pressed = event.state {
             let position = window.cursor_position();
-            if let Some(pos) = position {
+            if let Some(pos) = position.filter(|pos| !over_restart_button(window, *pos)) {
                 log::trace!("Mouse button pressed: {:?} at {}", event.button, pos);
-                let tile_coordinates = board.mouse_position(window, pos);
-                if let Some(coordinates) = tile_coordinates {
-                    match event.button {
-                        MouseButton::Left => {
-                            log::info!("Trying to uncover tile on {}", coordinates);
-                            tile_trigger_ewr.send(TileTriggerEvent(coordinates));
-                        }
-                        MouseButton::Right => {
-                            log::info!("Trying to make tile on {}", coordinates);
-                            tile_mark_event.send(TileMarkEvent(coordinates));
-                        }
-                        _ => {}
+                let target = boards.iter().find_map(|(entity, board, flags_match, primary)| {
+                    board
+                        .mouse_position(window, pos)
+                        .map(|coordinates| (entity, board, flags_match, primary, coordinates))
+                });
+                let Some((board_entity, board, flags_match, primary, coordinates)) = target else {
+                    continue;
+                };
+                // ネットワーク対戦はメインのボードだけで行う
+                let client = net_client.as_mut().filter(|_| primary);
+                if board.is_lost()
+                    || flags_match.is_some_and(|m| m.is_over())
+                    || client.as_ref().is_some_and(|c| c.finished.is_some())
+                {
+                    continue;
+                }
+                match (event.button, client) {
+                    // 接続している間はサーバーに要求し、認められたら`apply_server_messages`が開く
+                    (MouseButton::Left, Some(client)) => {
+                        client.send(&ClientMessage::Uncover(coordinates));
+                    }
+                    (MouseButton::Right, Some(client)) => {
+                        client.send(&ClientMessage::Mark(coordinates));
+                    }
+                    (MouseButton::Left, None) => {
+                        log::info!("Trying to uncover tile on {}", coordinates);
+                        tile_trigger_ewr.send(TileTriggerEvent { board: board_entity, coordinates, safe_start: false });
+                    }
+                    // 対戦モードでは旗を立てられない
+                    (MouseButton::Right, None) if flags_match.is_none() => {
+                        log::info!("Trying to make tile on {}", coordinates);
+                        tile_mark_event.send(TileMarkEvent { board: board_entity, coordinates });
                     }
+                    _ => {}
                 }
             }
         }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,40 @@
+use bevy::asset::LoadState;
+use bevy::prelude::*;
+use crate::events::BoardAssetsFailedEvent;
+use crate::resources::BoardAssets;
+
+/// `BoardAssets`のハンドルが全て読み込まれたら`true`
+///
+/// 失敗したハンドルはイベントで知らせて組み込みの見た目に置き換え、読み込み済みとして扱う。
+/// テーマのファイル自体が読めなければ`apply_theme`が組み込みの`BoardAssets`を入れる
+pub fn board_assets_loaded(
+    asset_server: Res<AssetServer>,
+    board_assets: Option<ResMut<BoardAssets>>,
+    mut failed_ewr: EventWriter<BoardAssetsFailedEvent>,
+) -> bool {
+    // テーマがまだ読み込まれていない
+    let Some(mut board_assets) = board_assets else {
+        return false;
+    };
+    let mut loaded = true;
+    for id in board_assets.handles() {
+        match asset_server.get_load_state(id) {
+            // 組み込みのハンドルはサーバーが管理していない
+            None | Some(LoadState::Loaded) => {}
+            Some(LoadState::Failed(error)) => {
+                let path = asset_server
+                    .get_path(id)
+                    .map(|p| p.to_string())
+                    .unwrap_or_else(|| format!("{:?}", id));
+                log::error!("Failed to load board asset {}: {}", path, error);
+                failed_ewr.send(BoardAssetsFailedEvent {
+                    path,
+                    error: error.to_string(),
+                });
+                board_assets.reset_handle(id);
+            }
+            Some(LoadState::NotLoaded | LoadState::Loading) => loaded = false,
+        }
+    }
+    loaded
+}
@@ -1,46 +1,113 @@
 use bevy::prelude::*;
-use crate::events::TileMarkEvent;
+use crate::components::{PopIn, ThemePart};
+use crate::events::{BoardCompletedEvent, FlagChangedEvent, SoundCueEvent, TileMarkEvent};
 use crate::resources::board::Board;
+use crate::resources::board_options::RenderMode;
+use crate::resources::sound::SoundCue;
 use crate::resources::BoardAssets;
+use crate::systems::contrast::spawn_flag_cue;
+use crate::systems::counter::spawn_bomb_count;
 
+/// タイルの旗を切り替える、旗が全ての爆弾と合えばクリアになる
+#[allow(clippy::too_many_arguments)]
 pub fn mark_tiles(
     mut commands: Commands,
-    mut board: ResMut<Board>,
+    mut boards: Query<&mut Board>,
     board_assets: Res<BoardAssets>,
     mut tile_mark_event_rdr: EventReader<TileMarkEvent>,
+    mut flag_changed_ewr: EventWriter<FlagChangedEvent>,
+    mut board_completed_ewr: EventWriter<BoardCompletedEvent>,
+    mut sound_cue_ewr: EventWriter<SoundCueEvent>,
     query: Query<&Children>,
 ) {
     for event in tile_mark_event_rdr.read() {
-        if let Some((entity, mark)) = board.try_toggle_mark(&event.0) {
-            if mark {
-                commands.entity(entity)
-                    .with_children(|parent| {
-                        parent
-                            .spawn(
-                                SpriteBundle {
-                                    texture: board_assets.flag_material.texture.clone(),
-                                    sprite: Sprite {
-                                        custom_size: Some(Vec2::splat(board.tile_size)),
-                                        ..Default::default()
-                                    },
-                                    transform: Transform::from_xyz(0., 0., 2.),
-                                    ..Default::default()
-                                }
-                            );
-                    })
-                    .insert(Name::new("Flag"));
-            } else {
-                let children = match query.get(entity) {
-                    Ok(c) => c,
-                    Err(e) => {
-                        log::error!("Failed to retrive flag entity components: {}", e);
-                        continue;
-                    }
-                };
+        let Ok(mut board) = boards.get_mut(event.board) else {
+            continue;
+        };
+        // チャンク描画のボードは`mark_chunk_tiles`が扱う
+        if matches!(board.render_mode, RenderMode::Chunked { .. }) {
+            continue;
+        }
+        if board.is_lost() || board.is_completed() {
+            continue;
+        }
+        if let Some((entity, level)) = board.try_cycle_mark(&event.coordinates) {
+            flag_changed_ewr.send(FlagChangedEvent {
+                board: event.board,
+                coordinates: event.coordinates,
+                level,
+                total: board.flag_total(),
+            });
+            sound_cue_ewr.send(SoundCueEvent::new(event.board, mark_cue(level)));
+            send_completed(&board, event.board, &mut board_completed_ewr, &mut sound_cue_ewr);
+            // 旗の数が変わったら古い旗を作り直す
+            if let Ok(children) = query.get(entity) {
                 for child in children.iter() {
                     commands.entity(*child).despawn_recursive();
                 }
             }
+            if level == 0 {
+                continue;
+            }
+            let flag_size = board.grid_shape.tile_sprite_size(board.tile_size).min_element();
+            let animations = board.animations;
+            let scale = if animations { Vec3::ZERO } else { Vec3::ONE };
+            commands.entity(entity)
+                .with_children(|parent| {
+                    let mut flag = parent
+                        .spawn(
+                            SpriteBundle {
+                                texture: board_assets.flag_material.texture.clone(),
+                                sprite: Sprite {
+                                    custom_size: Some(Vec2::splat(flag_size)),
+                                    color: board_assets.flag_material.color,
+                                    ..Default::default()
+                                },
+                                transform: Transform::from_xyz(0., 0., 2.).with_scale(scale),
+                                ..Default::default()
+                            }
+                        );
+                    flag.insert(Name::new("Flag"))
+                        .insert(ThemePart::Flag);
+                    if board_assets.high_contrast {
+                        flag.with_children(|parent| spawn_flag_cue(parent, None, flag_size));
+                    }
+                    if animations {
+                        flag.insert(PopIn::default());
+                    }
+                    if board.tile_map.max_bombs_per_tile() > 1 {
+                        spawn_bomb_count(
+                            parent,
+                            level,
+                            &board_assets,
+                            flag_size / 2.,
+                            Transform::from_xyz(flag_size / 4., -flag_size / 4., 3.),
+                        );
+                    }
+                });
         }
     }
-}
\ No newline at end of file
+}
+
+/// 旗でクリアしたらクリアのイベントと音を送る
+pub(crate) fn send_completed(
+    board: &Board,
+    entity: Entity,
+    board_completed_ewr: &mut EventWriter<BoardCompletedEvent>,
+    sound_cue_ewr: &mut EventWriter<SoundCueEvent>,
+) {
+    if board.is_completed() {
+        log::info!("Bomb Completed");
+        board_completed_ewr.send(BoardCompletedEvent { board: entity });
+        sound_cue_ewr.send(SoundCueEvent::new(entity, SoundCue::Completed));
+    }
+}
+
+/// 旗を立てたか外したかの音
+pub(crate) fn mark_cue(level: u8) -> SoundCue {
+    if level > 0 {
+        SoundCue::FlagOn
+    } else {
+        SoundCue::FlagOff
+    }
+}
@@ -1,2 +1,19 @@
+pub mod animation;
+pub mod chunk;
+pub mod contrast;
+pub mod counter;
+pub mod daily;
+pub mod flags;
+pub mod ghost;
+pub mod hud;
 pub mod input;
-pub mod mark;
\ No newline at end of file
+pub mod loading;
+pub mod mark;
+pub mod net;
+pub mod phase;
+pub mod resize;
+pub mod restart;
+pub mod score;
+pub mod settings;
+pub mod theme;
+pub mod uncover;
\ No newline at end of file
@@ -0,0 +1,36 @@
+use bevy::prelude::*;
+use crate::components::PrimaryBoard;
+use crate::events::{TileMarkEvent, TileTriggerEvent};
+use crate::net::client::NetClient;
+use crate::net::protocol::ServerMessage;
+
+/// サーバーが認めた操作をメインのボードに反映する
+///
+/// サーバーと同じシードのボードなので、同じタイルを開けば同じ結果になる
+pub fn apply_server_messages(
+    mut client: ResMut<NetClient>,
+    primary: Query<Entity, With<PrimaryBoard>>,
+    mut tile_trigger_ewr: EventWriter<TileTriggerEvent>,
+    mut tile_mark_ewr: EventWriter<TileMarkEvent>,
+) {
+    let Ok(board) = primary.get_single() else {
+        return;
+    };
+    // 毎フレームの受信で変更にすると、HUDが毎フレーム作り直される
+    for message in client.bypass_change_detection().poll() {
+        match message {
+            ServerMessage::Uncovered { coordinates, .. } => {
+                tile_trigger_ewr.send(TileTriggerEvent { board, coordinates, safe_start: false });
+            }
+            ServerMessage::Marked { coordinates, .. } => {
+                tile_mark_ewr.send(TileMarkEvent { board, coordinates });
+            }
+            ServerMessage::Finished { winner } => {
+                log::info!("Game finished, winner: {:?}", winner);
+                client.finished = Some(winner);
+            }
+            ServerMessage::Rejected { reason } => log::warn!("Rejected by server: {}", reason),
+            ServerMessage::Welcome(_) => {}
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use bevy::prelude::*;
+use crate::components::PrimaryBoard;
+use crate::events::{FlagChangedEvent, TilesRevealedEvent};
+use crate::net::client::NetClient;
+use crate::resources::board::Board;
+use crate::resources::flags_match::FlagsMatch;
+use crate::states::GamePhase;
+
+/// メインのボードの状態から`GamePhase`を進める
+///
+/// 終わったかはイベントではなくボードの状態で判断するので、同じフレームに爆発とクリアが重なっても移るのは1度だけ。
+/// 生成時の安全なタイルは開いてもプレイヤーの操作ではないので始めない
+pub fn advance_game_phase(
+    phase: Res<State<GamePhase>>,
+    mut next_phase: ResMut<NextState<GamePhase>>,
+    primary: Query<(Entity, &Board, Option<&FlagsMatch>), With<PrimaryBoard>>,
+    net_client: Option<Res<NetClient>>,
+    mut tiles_revealed_evr: EventReader<TilesRevealedEvent>,
+    mut flag_changed_evr: EventReader<FlagChangedEvent>,
+) {
+    let Ok((entity, board, flags_match)) = primary.get_single() else {
+        return;
+    };
+    let finished = match (net_client.as_ref().and_then(|c| c.finished.map(|w| (c.player, w))), flags_match) {
+        (Some((player, winner)), _) => Some(winner == Some(player)),
+        // 対戦モードは勝ち負けがプレイヤーごとなので、決着したら勝ちとして終わる
+        (None, Some(flags_match)) => flags_match.is_over().then_some(true),
+        (None, None) if board.is_lost() => Some(false),
+        (None, None) => board.is_completed().then_some(true),
+    };
+    // どちらのイベントも読み切る
+    let revealed = tiles_revealed_evr.read().filter(|e| e.board == entity && !e.safe_start).count() > 0;
+    let marked = flag_changed_evr.read().filter(|e| e.board == entity).count() > 0;
+    let acted = revealed || marked;
+    let next = match finished {
+        Some(true) => GamePhase::Won,
+        Some(false) => GamePhase::Lost,
+        None if acted && *phase.get() == GamePhase::NotStarted => GamePhase::Playing,
+        None => return,
+    };
+    log::info!("Game phase {:?} -> {:?}", phase.get(), next);
+    next_phase.set(next);
+}
+
+/// 一時停止の間は仮想時間を止める
+pub fn pause_time(mut time: ResMut<Time<Virtual>>) {
+    time.pause();
+}
+
+pub fn resume_time(mut time: ResMut<Time<Virtual>>) {
+    time.unpause();
+}
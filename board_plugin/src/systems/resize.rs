@@ -0,0 +1,139 @@
+use bevy::prelude::*;
+use bevy::window::WindowResized;
+use crate::components::{Coordinates, Ghost, TileChunk};
+use crate::resources::board::Board;
+use crate::resources::board_options::{BoardOptions, TileSize};
+
+/// ウィンドウの大きさが変わったときにボードを再配置する
+///
+/// 爆弾の配置は再生成せず、既存のスプライトの位置と大きさだけを更新する。
+/// 大きさと位置は各ボードの`BoardOptions`から計算する
+#[allow(clippy::too_many_arguments)]
+pub fn resize_board(
+    mut resize_evr: EventReader<WindowResized>,
+    mut boards: Query<(&mut Board, &BoardOptions)>,
+    children: Query<&Children>,
+    coordinates: Query<&Coordinates>,
+    chunks: Query<&TileChunk>,
+    ghosts: Query<&Ghost>,
+    mut transforms: Query<&mut Transform>,
+    mut sprites: Query<&mut Sprite>,
+    mut texts: Query<&mut Text>,
+) {
+    let Some(event) = resize_evr.read().last() else {
+        return;
+    };
+    for (mut board, options) in boards.iter_mut() {
+        if let TileSize::Fixed(_) = options.tile_size {
+            continue;
+        }
+        let map_size = (board.tile_map.width(), board.tile_map.height());
+        let tile_size = options.tile_size.compute(
+            Vec2::new(event.width, event.height),
+            options.board_tiles(),
+        );
+        if tile_size == board.tile_size {
+            continue;
+        }
+        let board_size = board.grid_shape.board_size(map_size, tile_size);
+        let board_position = options.position.translation(board_size);
+        log::info!("board resized: {}", board_size);
+
+        if let Ok(mut transform) = transforms.get_mut(board.entity) {
+            transform.translation = board_position;
+        }
+        let inner_size = board.grid_shape.tile_sprite_size(tile_size) - Vec2::splat(options.tile_padding);
+        let content_size = inner_size.min_element();
+        let flag_size = board.grid_shape.tile_sprite_size(tile_size).min_element();
+        for child in children.get(board.entity).into_iter().flatten() {
+            if let Ok(chunk) = chunks.get(*child) {
+                let chunk_size = Vec2::new(chunk.width as f32, chunk.height as f32) * tile_size;
+                if let Ok(mut transform) = transforms.get_mut(*child) {
+                    transform.translation.x = (chunk.origin.x as f32 * tile_size) + (chunk_size.x / 2.);
+                    transform.translation.y = (chunk.origin.y as f32 * tile_size) + (chunk_size.y / 2.);
+                }
+                if let Ok(mut sprite) = sprites.get_mut(*child) {
+                    sprite.custom_size = Some(chunk_size);
+                }
+                continue;
+            }
+            if let Ok(ghost) = ghosts.get(*child) {
+                if let Ok(mut transform) = transforms.get_mut(*child) {
+                    let center = board.grid_shape.wrapped_tile_center(ghost.source, ghost.offset, map_size, tile_size);
+                    transform.translation.x = center.x;
+                    transform.translation.y = center.y;
+                }
+                if let Ok(mut sprite) = sprites.get_mut(*child) {
+                    sprite.custom_size = Some(inner_size);
+                }
+                for content in ghost.content.iter().chain([&ghost.flag]) {
+                    if let Ok(mut sprite) = sprites.get_mut(*content) {
+                        sprite.custom_size = Some(Vec2::splat(content_size));
+                    }
+                    if let Ok(mut text) = texts.get_mut(*content) {
+                        for section in text.sections.iter_mut() {
+                            section.style.font_size = content_size;
+                        }
+                    }
+                }
+                continue;
+            }
+            let Ok(coords) = coordinates.get(*child) else {
+                // 背景
+                if let Ok(mut sprite) = sprites.get_mut(*child) {
+                    sprite.custom_size = Some(board_size);
+                }
+                if let Ok(mut transform) = transforms.get_mut(*child) {
+                    transform.translation.x = board_size.x / 2.;
+                    transform.translation.y = board_size.y / 2.;
+                }
+                continue;
+            };
+            if let Ok(mut transform) = transforms.get_mut(*child) {
+                let center = board.grid_shape.tile_center(*coords, tile_size);
+                transform.translation.x = center.x;
+                transform.translation.y = center.y;
+            }
+            if let Ok(mut sprite) = sprites.get_mut(*child) {
+                sprite.custom_size = Some(inner_size);
+            }
+            // 爆弾、数字、カバー
+            for content in children.get(*child).into_iter().flatten() {
+                if let Ok(mut sprite) = sprites.get_mut(*content) {
+                    sprite.custom_size = Some(if board.covered_entity(coords) == Some(*content) {
+                        inner_size
+                    } else {
+                        Vec2::splat(content_size)
+                    });
+                }
+                if let Ok(mut text) = texts.get_mut(*content) {
+                    for section in text.sections.iter_mut() {
+                        section.style.font_size = content_size;
+                    }
+                }
+                // カバーの子の旗は隙間なしの大きさで生成されている
+                for flag in children.get(*content).into_iter().flatten() {
+                    if let Ok(mut sprite) = sprites.get_mut(*flag) {
+                        sprite.custom_size = Some(Vec2::splat(flag_size));
+                    }
+                    if let Ok(mut text) = texts.get_mut(*flag) {
+                        for section in text.sections.iter_mut() {
+                            section.style.font_size = flag_size / 2.;
+                        }
+                    }
+                    if let Ok(mut transform) = transforms.get_mut(*flag) {
+                        if transform.translation.x != 0. {
+                            // 旗の数の表示は右下に寄せている
+                            transform.translation.x = flag_size / 4.;
+                            transform.translation.y = -flag_size / 4.;
+                        }
+                    }
+                }
+            }
+        }
+
+        board.bounds.position = board_position.xy();
+        board.bounds.size = board_size;
+        board.tile_size = tile_size;
+    }
+}
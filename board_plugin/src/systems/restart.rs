@@ -0,0 +1,90 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use crate::components::PrimaryBoard;
+use crate::events::{NewBoardEvent, RestartBoardEvent, TileTriggerEvent};
+use crate::net::client::NetClient;
+use crate::resources::board_options::BoardOptions;
+use crate::resources::daily::{DailyChallenge, DailyReplay};
+use crate::resources::BoardAssets;
+use crate::spawn_started_board;
+use crate::states::GamePhase;
+
+/// 状態を抜けずに要求されたボードを作り直す
+///
+/// 古いボードを削除して生成し直すので、ライフ、時間、対戦の得点も最初からになる。
+/// 同じボードへの新しいボードの要求はやり直しより優先する。
+/// メインのボードを作り直すとゲームの進み具合も戻り、新しいボードにするとデイリーチャレンジは終わる
+#[allow(clippy::too_many_arguments)]
+pub fn restart_board(
+    mut commands: Commands,
+    mut restart_evr: EventReader<RestartBoardEvent>,
+    mut new_board_evr: EventReader<NewBoardEvent>,
+    board_options: Option<Res<BoardOptions>>,
+    boards: Query<(&BoardOptions, Has<PrimaryBoard>)>,
+    board_assets: Res<BoardAssets>,
+    window: Query<&Window>,
+    mut images: ResMut<Assets<Image>>,
+    mut tile_trigger_ewr: EventWriter<TileTriggerEvent>,
+    time: Res<Time>,
+    mut next_phase: ResMut<NextState<GamePhase>>,
+    net_client: Option<Res<NetClient>>,
+    daily: Option<Res<DailyChallenge>>,
+) {
+    // ボードごとの新しいオプション、`None`ならやり直し
+    let mut requests: HashMap<Entity, Option<BoardOptions>> = HashMap::new();
+    for event in restart_evr.read() {
+        requests.entry(event.board).or_insert(None);
+    }
+    for event in new_board_evr.read() {
+        let options = event.options.clone().unwrap_or_else(|| BoardOptions {
+            seed: None,
+            ..board_options.as_deref().cloned().unwrap_or_default()
+        });
+        requests.insert(event.board, Some(options));
+    }
+    if requests.is_empty() {
+        return;
+    }
+    if net_client.is_some() {
+        log::warn!("Cannot restart a networked board");
+        return;
+    }
+    let window = window.single();
+    for (entity, new_options) in requests {
+        let Ok((old_options, primary)) = boards.get(entity) else {
+            log::warn!("No board {:?} to restart", entity);
+            continue;
+        };
+        let options = match new_options {
+            Some(options) => {
+                if primary {
+                    commands.remove_resource::<DailyChallenge>();
+                }
+                log::info!("New board for {:?}", entity);
+                options
+            }
+            // 生成したボードの`BoardOptions`にはシードが入っている
+            None => {
+                if primary && daily.is_some() {
+                    commands.insert_resource(DailyReplay::default());
+                }
+                log::info!("Restarting board {:?}", entity);
+                old_options.clone()
+            }
+        };
+        commands.entity(entity).despawn_recursive();
+        spawn_started_board(
+            &mut commands,
+            &options,
+            &board_assets,
+            window,
+            &mut images,
+            &mut tile_trigger_ewr,
+            time.elapsed(),
+            primary,
+        );
+        if primary {
+            next_phase.set(GamePhase::NotStarted);
+        }
+    }
+}
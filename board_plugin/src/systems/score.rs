@@ -0,0 +1,33 @@
+use bevy::prelude::*;
+use crate::components::PrimaryBoard;
+use crate::events::BoardCompletedEvent;
+use crate::resources::board::Board;
+use crate::resources::high_scores::{HighScores, Score, HIGH_SCORES_PATH};
+
+/// メインのボードをクリアしたらハイスコアに記録して保存する
+pub fn record_high_score(
+    time: Res<Time>,
+    boards: Query<&Board, With<PrimaryBoard>>,
+    mut high_scores: ResMut<HighScores>,
+    mut board_completed_evr: EventReader<BoardCompletedEvent>,
+) {
+    for event in board_completed_evr.read() {
+        let Ok(board) = boards.get(event.board) else {
+            continue;
+        };
+        let score = Score {
+            seconds: (time.elapsed() - board.started_at()).as_secs_f32(),
+            map_size: (board.tile_map.width(), board.tile_map.height()),
+            bomb_count: board.tile_map.bomb_count(),
+            lives_lost: board.lives_lost(),
+            max_lives: board.max_lives(),
+        };
+        log::info!("Completed in {:.1}s with {} lives lost", score.seconds, score.lives_lost);
+        if let Some(rank) = high_scores.record(score) {
+            log::info!("New high score, rank {}", rank + 1);
+            if let Err(e) = high_scores.save(HIGH_SCORES_PATH) {
+                log::error!("Failed to save high scores: {}", e);
+            }
+        }
+    }
+}
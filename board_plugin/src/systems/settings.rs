@@ -0,0 +1,9 @@
+use bevy::prelude::*;
+use crate::resources::settings::{BoardSettings, SETTINGS_PATH};
+
+/// 設定が変わったら保存する
+pub fn save_settings(settings: Res<BoardSettings>) {
+    if let Err(e) = settings.save(SETTINGS_PATH) {
+        log::error!("Failed to save settings: {}", e);
+    }
+}
@@ -0,0 +1,138 @@
+use bevy::asset::LoadState;
+use bevy::prelude::*;
+use crate::components::{BombCounter, ChunkRedraw, ThemePart, TileChunk};
+use crate::events::BoardAssetsFailedEvent;
+use crate::resources::settings::BoardSettings;
+use crate::resources::theme::{BoardTheme, Theme};
+use crate::resources::BoardAssets;
+use crate::systems::contrast::spawn_flag_cue;
+use crate::systems::counter::spawn_digits;
+
+/// テーマか設定が切り替わるか、今のテーマのファイルが変わったら`BoardAssets`を作り直す
+///
+/// テーマのファイルを読めなければイベントで知らせて組み込みの見た目にする
+#[allow(clippy::too_many_arguments)]
+pub fn apply_theme(
+    mut commands: Commands,
+    board_theme: Res<BoardTheme>,
+    settings: Res<BoardSettings>,
+    themes: Res<Assets<Theme>>,
+    asset_server: Res<AssetServer>,
+    mut layouts: ResMut<Assets<TextureAtlasLayout>>,
+    mut theme_evr: EventReader<AssetEvent<Theme>>,
+    mut failed_ewr: EventWriter<BoardAssetsFailedEvent>,
+    mut reported: Local<Option<AssetId<Theme>>>,
+) {
+    let Some(handle) = board_theme.handle() else {
+        return;
+    };
+    let mut changed = board_theme.is_changed() || settings.is_changed();
+    for event in theme_evr.read() {
+        changed |= event.is_loaded_with_dependencies(handle) || event.is_modified(handle);
+    }
+    if let Some(LoadState::Failed(error)) = asset_server.get_load_state(handle) {
+        // 同じ失敗は1度だけ知らせる
+        if *reported != Some(handle.id()) {
+            let path = asset_server
+                .get_path(handle)
+                .map(|p| p.to_string())
+                .unwrap_or_else(|| format!("{:?}", handle.id()));
+            log::error!("Failed to load theme {}: {}", path, error);
+            failed_ewr.send(BoardAssetsFailedEvent {
+                path,
+                error: error.to_string(),
+            });
+            *reported = Some(handle.id());
+            changed = true;
+        }
+        if changed {
+            let mut board_assets = BoardAssets::default();
+            settings.apply(&mut board_assets);
+            commands.insert_resource(board_assets);
+        }
+        return;
+    }
+    if !changed {
+        return;
+    }
+    // 読み込み中ならそのうち`LoadedWithDependencies`が届く
+    let Some(theme) = themes.get(handle) else {
+        return;
+    };
+    *reported = None;
+    log::info!("Applying theme {}", theme.label);
+    let mut board_assets = theme.board_assets(&asset_server, &mut layouts);
+    settings.apply(&mut board_assets);
+    commands.insert_resource(board_assets);
+}
+
+/// テーマで見た目が変わる部分
+type ThemedQuery<'w, 's> = Query<
+    'w,
+    's,
+    (Entity, &'static ThemePart, Option<&'static mut Sprite>, Option<&'static mut Handle<Image>>, Option<&'static mut Text>),
+>;
+
+/// `BoardAssets`が変わったら、ボードのスプライトと数字に付け直す
+pub fn restyle_board(
+    mut commands: Commands,
+    board_assets: Res<BoardAssets>,
+    mut parts: ThemedQuery,
+    counters: Query<(Entity, &BombCounter)>,
+    chunks: Query<Entity, With<TileChunk>>,
+) {
+    // 数字はアトラス、フォント、組み込みのどれで描くかが変わるので作り直す
+    for (entity, counter) in counters.iter() {
+        commands
+            .entity(entity)
+            .despawn_descendants()
+            .with_children(|parent| spawn_digits(parent, counter.count, &board_assets, counter.size));
+    }
+    for (entity, part, sprite, texture, text) in parts.iter_mut() {
+        let material = match part {
+            ThemePart::Background => &board_assets.board_material,
+            ThemePart::Tile => &board_assets.tile_material,
+            ThemePart::Cover => &board_assets.covered_tile_material,
+            ThemePart::Bomb => &board_assets.bomb_material,
+            ThemePart::Flag | ThemePart::PlayerFlag(_) => &board_assets.flag_material,
+            ThemePart::Counter(count) => {
+                let color = board_assets.bomb_counter_color(*count);
+                if let Some(mut text) = text {
+                    for section in text.sections.iter_mut() {
+                        section.style.color = color;
+                        section.style.font = board_assets.bomb_counter_font.clone();
+                    }
+                }
+                continue;
+            }
+        };
+        let color = match part {
+            ThemePart::PlayerFlag(player) => board_assets.player_flag_color(*player),
+            _ => material.color,
+        };
+        // 旗の印はハイコントラストの切り替えに合わせて作り直す
+        if let (ThemePart::Flag | ThemePart::PlayerFlag(_), Some(size)) =
+            (part, sprite.as_ref().and_then(|s| s.custom_size))
+        {
+            let player = match part {
+                ThemePart::PlayerFlag(player) => Some(*player),
+                _ => None,
+            };
+            let mut flag = commands.entity(entity);
+            flag.despawn_descendants();
+            if board_assets.high_contrast {
+                flag.with_children(|parent| spawn_flag_cue(parent, player, size.x));
+            }
+        }
+        if let Some(mut sprite) = sprite {
+            // 端の複製は透明度を残す
+            sprite.color = color.with_alpha(sprite.color.alpha());
+        }
+        if let Some(mut texture) = texture {
+            *texture = material.texture.clone();
+        }
+    }
+    for entity in chunks.iter() {
+        commands.entity(entity).insert(ChunkRedraw);
+    }
+}
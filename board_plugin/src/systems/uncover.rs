@@ -0,0 +1,139 @@
+use bevy::prelude::*;
+use bevy::utils::{HashMap, HashSet};
+use crate::components::{ChunkRedraw, Coordinates, PopIn, ThemePart};
+use crate::events::{BoardCompletedEvent, BombExplosionEvent, SoundCueEvent, TileTriggerEvent, TilesRevealedEvent};
+use crate::resources::board::Board;
+use crate::resources::board_options::RenderMode;
+use crate::resources::flags_match::FlagsMatch;
+use crate::resources::sound::SoundCue;
+use crate::resources::BoardAssets;
+use crate::systems::animation::{reveal_wave, spawn_explosion};
+use crate::systems::contrast::spawn_flag_cue;
+
+/// クリックされたタイルを開く
+///
+/// 空のタイルが繋がっている範囲は`Board::uncover_region`で1回で計算し、
+/// カバーはまとめて削除する。開いている数字のタイルはコードとして周囲を開く。
+/// 爆弾を開くとライフを1つ失い、ライフが無くなるかクリアしたらそれ以降のクリックは無視する。
+/// 対戦モードのボードは`flags_uncover_tiles`が開く
+#[allow(clippy::too_many_arguments)]
+pub fn uncover_tiles(
+    mut commands: Commands,
+    mut boards: Query<&mut Board, Without<FlagsMatch>>,
+    board_assets: Res<BoardAssets>,
+    mut tile_trigger_event: EventReader<TileTriggerEvent>,
+    mut board_completed_event_wr: EventWriter<BoardCompletedEvent>,
+    mut bomb_explosion_event_wr: EventWriter<BombExplosionEvent>,
+    mut tiles_revealed_ewr: EventWriter<TilesRevealedEvent>,
+    mut sound_cue_ewr: EventWriter<SoundCueEvent>,
+    parents: Query<&Parent>,
+) {
+    // カバーの削除はボードごとにまとめる
+    let mut uncovered: HashMap<Entity, Vec<(Coordinates, Entity)>> = HashMap::new();
+    for trigger_event in tile_trigger_event.read() {
+        let Ok(mut board) = boards.get_mut(trigger_event.board) else {
+            continue;
+        };
+        // 終わったボードは開かない、クリアや爆発を何度も送らないようにする
+        if board.is_lost() || board.is_completed() {
+            continue;
+        }
+        for target in board.trigger_targets(trigger_event.coordinates) {
+            let region = board.uncover_region(target);
+            if region.is_empty() {
+                continue;
+            }
+            log::debug!("Uncovered {} tiles from {}", region.len(), target);
+            let tiles = region.len();
+            // 爆弾のタイルはカバーの親
+            let mine_tile = region.first().and_then(|(_, cover)| parents.get(*cover).ok()).map(Parent::get);
+            uncovered.entry(trigger_event.board).or_default().extend(region);
+            tiles_revealed_ewr.send(TilesRevealedEvent {
+                board: trigger_event.board,
+                coordinates: target,
+                count: tiles,
+                safe_start: trigger_event.safe_start,
+            });
+            if !board.tile_map.is_bomb_at(target) {
+                sound_cue_ewr.send(SoundCueEvent::reveal(trigger_event.board, tiles));
+            } else {
+                sound_cue_ewr.send(SoundCueEvent::new(trigger_event.board, SoundCue::Explosion));
+                let lives = board.detonate(&target);
+                log::info!("Boom ! {} lives left", lives);
+                bomb_explosion_event_wr.send(BombExplosionEvent { board: trigger_event.board, coordinates: target });
+                if let (RenderMode::Entities, Some(tile)) = (board.render_mode, mine_tile) {
+                    spawn_auto_flag(&mut commands, &board, &board_assets, tile);
+                }
+                if board.animations {
+                    spawn_explosion(&mut commands, &board, &board_assets, target);
+                }
+                if lives == 0 {
+                    break;
+                }
+            }
+        }
+    }
+
+    for (entity, uncovered) in uncovered {
+        let Ok(board) = boards.get(entity) else {
+            continue;
+        };
+        remove_covers(&mut commands, board, uncovered);
+        if board.is_completed() {
+            log::info!("Bomb Completed");
+            board_completed_event_wr.send(BoardCompletedEvent { board: entity });
+            sound_cue_ewr.send(SoundCueEvent::new(entity, SoundCue::Completed));
+        }
+    }
+}
+
+/// 爆発した爆弾のタイルの角に自動で立てた旗を表示する
+fn spawn_auto_flag(commands: &mut Commands, board: &Board, board_assets: &BoardAssets, tile: Entity) {
+    // 爆弾が見えるように小さくする
+    let flag_size = board.grid_shape.tile_sprite_size(board.tile_size).min_element() / 2.;
+    let scale = if board.animations { Vec3::ZERO } else { Vec3::ONE };
+    commands.entity(tile).with_children(|parent| {
+        let mut flag = parent.spawn(SpriteBundle {
+            texture: board_assets.flag_material.texture.clone(),
+            sprite: Sprite {
+                color: board_assets.flag_material.color,
+                custom_size: Some(Vec2::splat(flag_size)),
+                ..Default::default()
+            },
+            transform: Transform::from_xyz(flag_size / 2., flag_size / 2., 3.).with_scale(scale),
+            ..Default::default()
+        });
+        flag.insert(Name::new("Flag"))
+            .insert(ThemePart::Flag);
+        if board_assets.high_contrast {
+            flag.with_children(|parent| spawn_flag_cue(parent, None, flag_size));
+        }
+        if board.animations {
+            flag.insert(PopIn::default());
+        }
+    });
+}
+
+/// 開いたタイルのカバーをまとめて削除する、チャンク描画ではチャンクを描き直す
+///
+/// アニメーションがあればカバーは波のように縮んでから消える
+pub(crate) fn remove_covers(
+    commands: &mut Commands,
+    board: &Board,
+    uncovered: Vec<(Coordinates, Entity)>,
+) {
+    match board.render_mode {
+        RenderMode::Entities if board.animations => reveal_wave(commands, board, &uncovered),
+        RenderMode::Entities => {
+            for (_, cover) in uncovered {
+                commands.entity(cover).despawn_recursive();
+            }
+        }
+        RenderMode::Chunked { .. } => {
+            let chunks: HashSet<Entity> = uncovered.into_iter().map(|(_, chunk)| chunk).collect();
+            for chunk in chunks {
+                commands.entity(chunk).insert(ChunkRedraw);
+            }
+        }
+    }
+}
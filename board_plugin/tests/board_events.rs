@@ -0,0 +1,60 @@
+//! ボードの操作から送られる公開イベントの中身を確かめる
+mod common;
+
+use bevy::prelude::*;
+use board_plugin::events::{BombExplosionEvent, FlagChangedEvent, GameStartedEvent, TileMarkEvent, TilesRevealedEvent};
+use board_plugin::resources::board::Board;
+use board_plugin::resources::tile::Tile;
+use common::{board_app, events_after, find_tile, options, primary_board, trigger};
+
+#[test]
+fn new_board_reports_its_seed() {
+    let mut app = board_app(options());
+    let board = primary_board(&mut app);
+    let started: Vec<_> = app.world_mut().resource_mut::<Events<GameStartedEvent>>().drain().collect();
+    assert_eq!(started.len(), 1);
+    assert_eq!(started[0].board, board);
+    assert_eq!(started[0].seed, 7);
+}
+
+#[test]
+fn empty_tile_reports_the_whole_region() {
+    let mut app = board_app(options());
+    let board = primary_board(&mut app);
+    let coordinates = find_tile(&app, board, |t| t == Tile::Empty);
+    let revealed = events_after::<TilesRevealedEvent>(&mut app, |world| {
+        world.send_event(trigger(board, coordinates));
+    });
+    assert_eq!(revealed.len(), 1);
+    assert_eq!((revealed[0].board, revealed[0].coordinates), (board, coordinates));
+    let state = app.world().get::<Board>(board).unwrap();
+    assert!(revealed[0].count > 1);
+    assert_eq!(revealed[0].count, state.tile_map.tile_count() - state.covered_count());
+}
+
+#[test]
+fn mine_reports_where_it_exploded() {
+    let mut app = board_app(options());
+    let board = primary_board(&mut app);
+    let coordinates = find_tile(&app, board, |t| t.is_bomb());
+    let explosions = events_after::<BombExplosionEvent>(&mut app, |world| {
+        world.send_event(trigger(board, coordinates));
+    });
+    assert_eq!(explosions.len(), 1);
+    assert_eq!((explosions[0].board, explosions[0].coordinates), (board, coordinates));
+}
+
+#[test]
+fn flags_report_level_and_total() {
+    let mut app = board_app(options());
+    let board = primary_board(&mut app);
+    let coordinates = find_tile(&app, board, |t| t.is_bomb());
+    let mark = move |world: &mut World| {
+        world.send_event(TileMarkEvent { board, coordinates });
+    };
+    let placed = events_after::<FlagChangedEvent>(&mut app, mark);
+    assert_eq!(placed.len(), 1);
+    assert_eq!((placed[0].coordinates, placed[0].level, placed[0].total), (coordinates, 1, 1));
+    let removed = events_after::<FlagChangedEvent>(&mut app, mark);
+    assert_eq!((removed[0].level, removed[0].total), (0, 0));
+}
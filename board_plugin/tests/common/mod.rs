@@ -0,0 +1,85 @@
+//! 統合テストで共有する、ウィンドウと音声なしでボードを動かすアプリ
+// テストのファイルごとに使う関数が違う
+#![allow(dead_code)]
+use bevy::input::mouse::MouseButtonInput;
+use bevy::prelude::*;
+use bevy::state::app::StatesPlugin;
+use bevy::window::{WindowResized, WindowResolution};
+use board_plugin::BoardPlugin;
+use board_plugin::components::{Coordinates, PrimaryBoard};
+use board_plugin::events::TileTriggerEvent;
+use board_plugin::resources::board::Board;
+use board_plugin::resources::board_options::BoardOptions;
+use board_plugin::resources::tile::Tile;
+use board_plugin::resources::BoardAssets;
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash, States)]
+pub struct Running;
+
+/// シードを決めた10x10のボードのオプション
+pub fn options() -> BoardOptions {
+    BoardOptions {
+        map_size: (10, 10),
+        bomb_count: 10,
+        seed: Some(7),
+        ..Default::default()
+    }
+}
+
+/// `options`のボードを生成したアプリ
+pub fn board_app(options: BoardOptions) -> App {
+    let mut app = App::new();
+    // ワークスペースでは`file_watcher`が有効になるが、このクレートには`assets`がない
+    let assets = AssetPlugin {
+        watch_for_changes_override: Some(false),
+        ..Default::default()
+    };
+    app.add_plugins((MinimalPlugins, assets, StatesPlugin))
+        .init_asset::<Image>()
+        .add_event::<MouseButtonInput>()
+        .add_event::<WindowResized>()
+        .insert_resource(options)
+        .insert_resource(BoardAssets::default())
+        .insert_state(Running)
+        .add_plugins(BoardPlugin { running_state: Running, loading_state: None });
+    app.world_mut().spawn(Window {
+        resolution: WindowResolution::new(400., 400.),
+        ..Default::default()
+    });
+    app.update();
+    app
+}
+
+pub fn primary_board(app: &mut App) -> Entity {
+    app.world_mut()
+        .query_filtered::<Entity, With<PrimaryBoard>>()
+        .single(app.world())
+}
+
+/// 条件に合うタイル
+pub fn find_tiles(app: &App, board: Entity, matches: impl Fn(Tile) -> bool) -> Vec<Coordinates> {
+    let board = app.world().get::<Board>(board).expect("board");
+    board
+        .tile_map
+        .iter()
+        .filter(|(_, tile)| matches(*tile))
+        .map(|(coords, _)| coords)
+        .collect()
+}
+
+/// 条件に合う最初のタイル
+pub fn find_tile(app: &App, board: Entity, matches: impl Fn(Tile) -> bool) -> Coordinates {
+    find_tiles(app, board, matches).into_iter().next().expect("tile")
+}
+
+pub fn trigger(board: Entity, coordinates: Coordinates) -> TileTriggerEvent {
+    TileTriggerEvent { board, coordinates, safe_start: false }
+}
+
+/// イベントを送って1フレーム進め、`E`のイベントを返す
+pub fn events_after<E: Event>(app: &mut App, send: impl FnOnce(&mut World)) -> Vec<E> {
+    app.world_mut().resource_mut::<Events<E>>().clear();
+    send(app.world_mut());
+    app.update();
+    app.world_mut().resource_mut::<Events<E>>().drain().collect()
+}
@@ -0,0 +1,81 @@
+//! 対戦モードの手番と得点の決まりを、ボードの操作から確かめる
+mod common;
+
+use bevy::prelude::*;
+use board_plugin::events::{MineClaimedEvent, TileTriggerEvent, TurnChangedEvent};
+use board_plugin::resources::board::Board;
+use board_plugin::resources::board_options::{BoardOptions, GameMode};
+use board_plugin::resources::flags_match::FlagsMatch;
+use board_plugin::resources::tile::Tile;
+use common::{board_app, events_after, find_tile, options, primary_board, trigger};
+
+fn flags_options() -> BoardOptions {
+    BoardOptions {
+        mode: GameMode::Flags,
+        max_bombs_per_tile: 2,
+        bomb_count: 14,
+        ..options()
+    }
+}
+
+fn flags_match(app: &App, board: Entity) -> FlagsMatch {
+    app.world().get::<FlagsMatch>(board).expect("flags match").clone()
+}
+
+#[test]
+fn claiming_a_mine_scores_its_bombs_and_keeps_the_turn() {
+    let mut app = board_app(flags_options());
+    let board = primary_board(&mut app);
+    let coordinates = find_tile(&app, board, |t| t == Tile::Bomb(2));
+    let turns = events_after::<TurnChangedEvent>(&mut app, |world| {
+        world.send_event(trigger(board, coordinates));
+    });
+    assert!(turns.is_empty());
+    let flags_match = flags_match(&app, board);
+    assert_eq!(flags_match.current_player(), 0);
+    assert_eq!(flags_match.score(0), 2);
+    assert_eq!(flags_match.score(1), 0);
+    let owner = app.world().get::<Board>(board).unwrap().mine_owner(&coordinates);
+    assert_eq!(owner, Some(0));
+}
+
+#[test]
+fn claim_is_sent_for_the_current_player() {
+    let mut app = board_app(flags_options());
+    let board = primary_board(&mut app);
+    let safe = find_tile(&app, board, |t| matches!(t, Tile::BombNeighbor(_)));
+    let mine = find_tile(&app, board, |t| t.is_bomb());
+    app.world_mut().send_event(trigger(board, safe));
+    app.update();
+    let claims = events_after::<MineClaimedEvent>(&mut app, |world| {
+        world.send_event(trigger(board, mine));
+    });
+    assert_eq!(claims.len(), 1);
+    assert_eq!(claims[0].player, 1);
+    assert_eq!(claims[0].coordinates, mine);
+}
+
+#[test]
+fn safe_reveal_passes_the_turn() {
+    let mut app = board_app(flags_options());
+    let board = primary_board(&mut app);
+    let coordinates = find_tile(&app, board, |t| matches!(t, Tile::BombNeighbor(_)));
+    let turns = events_after::<TurnChangedEvent>(&mut app, |world| {
+        world.send_event(trigger(board, coordinates));
+    });
+    assert_eq!(turns.len(), 1);
+    assert_eq!(turns[0].player, 1);
+    assert_eq!(flags_match(&app, board).current_player(), 1);
+}
+
+#[test]
+fn safe_start_reveal_keeps_the_turn() {
+    let mut app = board_app(flags_options());
+    let board = primary_board(&mut app);
+    let coordinates = find_tile(&app, board, |t| t == Tile::Empty);
+    let turns = events_after::<TurnChangedEvent>(&mut app, |world| {
+        world.send_event(TileTriggerEvent { board, coordinates, safe_start: true });
+    });
+    assert!(turns.is_empty());
+    assert_eq!(flags_match(&app, board).current_player(), 0);
+}
@@ -0,0 +1,91 @@
+//! `GamePhase`がゲームの終わりに1度だけ移ることを確かめる
+mod common;
+
+use bevy::prelude::*;
+use board_plugin::components::Coordinates;
+use board_plugin::resources::board::Board;
+use board_plugin::resources::board_options::BoardOptions;
+use board_plugin::resources::tile::Tile;
+use board_plugin::states::GamePhase;
+use common::{board_app, find_tile, find_tiles, options, primary_board, trigger};
+
+/// イベントを送って数フレーム進め、その間に移った`GamePhase`を返す
+///
+/// 次の状態はフレームの終わりに決まり、次のフレームの始めに移る
+fn entered_after(app: &mut App, send: impl FnOnce(&mut World)) -> Vec<GamePhase> {
+    app.world_mut().resource_mut::<Events<StateTransitionEvent<GamePhase>>>().clear();
+    send(app.world_mut());
+    for _ in 0..3 {
+        app.update();
+    }
+    app.world_mut()
+        .resource_mut::<Events<StateTransitionEvent<GamePhase>>>()
+        .drain()
+        .filter_map(|e| e.entered)
+        .collect()
+}
+
+/// 爆弾と、最後に開く連鎖しない安全なタイル以外を開いたボード
+struct NearlyCleared {
+    app: App,
+    board: Entity,
+    last: Coordinates,
+    mine: Coordinates,
+}
+
+fn nearly_cleared(lives: u8) -> NearlyCleared {
+    let mut app = board_app(BoardOptions { lives, ..options() });
+    let board = primary_board(&mut app);
+    let tile_map = &app.world().get::<Board>(board).unwrap().tile_map;
+    // 空のタイルの連鎖で一緒に開かれない数字のタイル
+    let last = tile_map
+        .iter()
+        .find(|(c, t)| {
+            matches!(t, Tile::BombNeighbor(_))
+                && tile_map.neighbors(*c).all(|n| tile_map.get(n) != Some(Tile::Empty))
+        })
+        .map(|(c, _)| c)
+        .expect("isolated number tile");
+    let mine = find_tile(&app, board, |t| t.is_bomb());
+    let safe: Vec<_> = find_tiles(&app, board, |t| !t.is_bomb())
+        .into_iter()
+        .filter(|c| *c != last)
+        .collect();
+    let entered = entered_after(&mut app, |world| {
+        world.send_event_batch(safe.into_iter().map(|c| trigger(board, c)));
+    });
+    assert_eq!(entered, vec![GamePhase::Playing]);
+    NearlyCleared { app, board, last, mine }
+}
+
+#[test]
+fn clearing_while_detonating_wins_once() {
+    let NearlyCleared { mut app, board, last, mine } = nearly_cleared(2);
+    let entered = entered_after(&mut app, |world| {
+        world.send_event_batch([trigger(board, mine), trigger(board, last)]);
+    });
+    assert_eq!(entered, vec![GamePhase::Won]);
+}
+
+#[test]
+fn losing_the_last_life_with_the_final_reveal_loses_once() {
+    // 先に開いたタイルで終わるので、爆発してから最後のタイルを開く
+    let NearlyCleared { mut app, board, last, mine } = nearly_cleared(1);
+    let entered = entered_after(&mut app, |world| {
+        world.send_event_batch([trigger(board, mine), trigger(board, last)]);
+    });
+    assert_eq!(entered, vec![GamePhase::Lost]);
+}
+
+#[test]
+fn finished_game_does_not_move_again() {
+    let NearlyCleared { mut app, board, last, mine } = nearly_cleared(2);
+    entered_after(&mut app, |world| {
+        world.send_event(trigger(board, last));
+    });
+    let entered = entered_after(&mut app, |world| {
+        world.send_event(trigger(board, mine));
+    });
+    assert!(entered.is_empty());
+    assert_eq!(*app.world().resource::<State<GamePhase>>().get(), GamePhase::Won);
+}
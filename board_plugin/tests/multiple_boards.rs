@@ -0,0 +1,51 @@
+//! 1つのワールドで複数のボードがそれぞれの状態で動くことを確かめる
+mod common;
+
+use std::time::Duration;
+use bevy::ecs::system::RunSystemOnce;
+use bevy::prelude::*;
+use board_plugin::components::PrimaryBoard;
+use board_plugin::resources::board::Board;
+use board_plugin::resources::board_options::BoardOptions;
+use board_plugin::resources::BoardAssets;
+use board_plugin::spawn_board;
+use common::{board_app, options, primary_board};
+
+/// `BoardPlugin`の外でボードを生成する
+fn spawn_extra_board(app: &mut App, options: BoardOptions) -> Entity {
+    let board = app.world_mut().run_system_once(
+        move |mut commands: Commands, board_assets: Res<BoardAssets>, mut images: ResMut<Assets<Image>>| {
+            let window_size = Vec2::new(400., 400.);
+            spawn_board(&mut commands, &options, &board_assets, window_size, &mut images, Duration::ZERO).entity
+        },
+    );
+    app.update();
+    board
+}
+
+#[test]
+fn extra_board_keeps_its_own_state() {
+    let mut app = board_app(BoardOptions { safe_start: true, ..options() });
+    let primary = primary_board(&mut app);
+    let extra = spawn_extra_board(&mut app, options());
+    assert!(app.world().get::<PrimaryBoard>(extra).is_none());
+
+    // 安全なスタートはメインのボードだけを開く
+    let primary = app.world().get::<Board>(primary).unwrap();
+    let extra = app.world().get::<Board>(extra).unwrap();
+    assert!(primary.covered_count() < primary.tile_map.tile_count());
+    assert_eq!(extra.covered_count(), extra.tile_map.tile_count());
+    // 同じシードなら同じ配置になる
+    assert!(primary.tile_map.iter().eq(extra.tile_map.iter()));
+}
+
+#[test]
+fn removing_an_extra_board_keeps_the_primary_one() {
+    let mut app = board_app(options());
+    let primary = primary_board(&mut app);
+    let extra = spawn_extra_board(&mut app, options());
+    app.world_mut().entity_mut(extra).despawn_recursive();
+    app.update();
+    assert_eq!(primary_board(&mut app), primary);
+    assert_eq!(app.world_mut().query::<&Board>().iter(app.world()).count(), 1);
+}
@@ -0,0 +1,66 @@
+//! ボードのやり直しと新しいボードで、メインのボードとゲームの進み具合が戻ることを確かめる
+mod common;
+
+use bevy::prelude::*;
+use board_plugin::components::PrimaryBoard;
+use board_plugin::events::{NewBoardEvent, RestartBoardEvent};
+use board_plugin::resources::board::Board;
+use board_plugin::resources::board_options::BoardOptions;
+use board_plugin::resources::tile::Tile;
+use board_plugin::states::GamePhase;
+use common::{board_app, find_tile, options, primary_board, trigger};
+
+fn phase(app: &App) -> GamePhase {
+    *app.world().resource::<State<GamePhase>>().get()
+}
+
+/// 1つタイルを開いて`Playing`にしたアプリ
+fn playing_app() -> (App, Entity) {
+    let mut app = board_app(options());
+    let board = primary_board(&mut app);
+    let coordinates = find_tile(&app, board, |t| matches!(t, Tile::BombNeighbor(_)));
+    app.world_mut().send_event(trigger(board, coordinates));
+    app.update();
+    app.update();
+    assert_eq!(phase(&app), GamePhase::Playing);
+    (app, board)
+}
+
+#[test]
+fn restart_resets_phase_and_replaces_primary_board() {
+    let (mut app, old) = playing_app();
+    let options = app.world().get::<BoardOptions>(old).unwrap().clone();
+    app.world_mut().send_event(RestartBoardEvent { board: old });
+    app.update();
+    app.update();
+
+    assert_eq!(phase(&app), GamePhase::NotStarted);
+    assert!(app.world().get_entity(old).is_none());
+    let new = primary_board(&mut app);
+    assert_ne!(new, old);
+    let board = app.world().get::<Board>(new).unwrap();
+    assert_eq!(board.covered_count(), board.tile_map.tile_count());
+    // やり直しは同じシードのボード
+    assert_eq!(app.world().get::<BoardOptions>(new).unwrap().seed, options.seed);
+}
+
+#[test]
+fn new_board_resets_phase_with_new_options() {
+    let (mut app, old) = playing_app();
+    let options = BoardOptions { map_size: (12, 8), seed: Some(3), ..options() };
+    app.world_mut().send_event(NewBoardEvent { board: old, options: Some(options) });
+    app.update();
+    app.update();
+
+    assert_eq!(phase(&app), GamePhase::NotStarted);
+    let new = primary_board(&mut app);
+    assert_ne!(new, old);
+    let primaries = app
+        .world_mut()
+        .query_filtered::<Entity, With<PrimaryBoard>>()
+        .iter(app.world())
+        .count();
+    assert_eq!(primaries, 1);
+    let tile_map = &app.world().get::<Board>(new).unwrap().tile_map;
+    assert_eq!((tile_map.width(), tile_map.height()), (12, 8));
+}
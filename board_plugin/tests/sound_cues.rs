@@ -0,0 +1,58 @@
+//! ウィンドウと音声なしで、ボードの操作から送られる`SoundCueEvent`を確かめる
+mod common;
+
+use board_plugin::events::{SoundCueEvent, TileMarkEvent};
+use board_plugin::resources::sound::SoundCue;
+use board_plugin::resources::tile::Tile;
+use common::{board_app, events_after, find_tile, options, primary_board, trigger};
+
+#[test]
+fn number_tile_sends_reveal() {
+    let mut app = board_app(options());
+    let board = primary_board(&mut app);
+    let coords = find_tile(&app, board, |t| matches!(t, Tile::BombNeighbor(_)));
+    let cues = events_after::<SoundCueEvent>(&mut app, |world| {
+        world.send_event(trigger(board, coords));
+    });
+    assert_eq!(cues, vec![SoundCueEvent { board, cue: SoundCue::Reveal, tiles: 1 }]);
+}
+
+#[test]
+fn empty_tile_sends_cascade() {
+    let mut app = board_app(options());
+    let board = primary_board(&mut app);
+    let coords = find_tile(&app, board, |t| t == Tile::Empty);
+    let cues = events_after::<SoundCueEvent>(&mut app, |world| {
+        world.send_event(trigger(board, coords));
+    });
+    assert_eq!(cues.len(), 1);
+    assert_eq!(cues[0].board, board);
+    assert_eq!(cues[0].cue, SoundCue::Cascade);
+    assert!(cues[0].tiles > 1);
+}
+
+#[test]
+fn mine_sends_explosion() {
+    let mut app = board_app(options());
+    let board = primary_board(&mut app);
+    let coords = find_tile(&app, board, |t| t.is_bomb());
+    let cues = events_after::<SoundCueEvent>(&mut app, |world| {
+        world.send_event(trigger(board, coords));
+    });
+    assert_eq!(cues, vec![SoundCueEvent { board, cue: SoundCue::Explosion, tiles: 1 }]);
+}
+
+#[test]
+fn marking_twice_sends_flag_on_then_off() {
+    let mut app = board_app(options());
+    let board = primary_board(&mut app);
+    let coordinates = find_tile(&app, board, |t| !t.is_bomb());
+    let on = events_after::<SoundCueEvent>(&mut app, |world| {
+        world.send_event(TileMarkEvent { board, coordinates });
+    });
+    let off = events_after::<SoundCueEvent>(&mut app, |world| {
+        world.send_event(TileMarkEvent { board, coordinates });
+    });
+    assert_eq!(on, vec![SoundCueEvent { board, cue: SoundCue::FlagOn, tiles: 1 }]);
+    assert_eq!(off, vec![SoundCueEvent { board, cue: SoundCue::FlagOff, tiles: 1 }]);
+}
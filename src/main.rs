@@ -1,8 +1,5 @@
-#![allow(unused)]
-
-use bevy::color::palettes::css::{DARK_GRAY, GRAY, WHITE};
-use bevy::input::common_conditions::input_just_pressed;
 use bevy::input::keyboard::KeyboardInput;
+use bevy::input::ButtonState;
 use bevy::log;
 use bevy::prelude::*;
 use bevy::window::WindowResolution;
@@ -10,22 +7,72 @@ use bevy::window::WindowResolution;
 #[cfg(feature = "debug")]
 use bevy_inspector_egui::quick::WorldInspectorPlugin;
 use board_plugin::BoardPlugin;
+use board_plugin::components::PrimaryBoard;
+use board_plugin::events::{NewBoardEvent, RestartBoardEvent};
+use board_plugin::net::client::NetClient;
+use board_plugin::net::protocol::NetMode;
+use board_plugin::net::server::{self, ServerConfig};
+use board_plugin::net::DEFAULT_PORT;
 use board_plugin::resources::board_options::BoardOptions;
-use board_plugin::resources::{BoardAssets, SpriteMaterial};
+use board_plugin::resources::theme::BoardTheme;
+use board_plugin::resources::settings::{BoardSettings, SETTINGS_PATH};
+use board_plugin::resources::BoardAssets;
+use board_plugin::states::GamePhase;
+use title_plugin::TitlePlugin;
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash, Resource, States)]
 pub enum AppState {
     Title,
+    Loading,
     InGame,
     Out,
 }
 
+/// `--server [versus|coop] [port]`でサーバー、`--connect [addr]`でクライアントとして起動する
 fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        Some("--server") => return run_server(&args[1..]),
+        Some("--connect") => {
+            let addr = args.get(1).cloned().unwrap_or(format!("127.0.0.1:{}", DEFAULT_PORT));
+            return run_game(Some(addr));
+        }
+        _ => {}
+    }
+    run_game(None);
+}
+
+fn run_server(args: &[String]) {
+    // ログの出力先だけを使う
+    App::new().add_plugins(bevy::log::LogPlugin::default());
+    let mode = match args.first().map(String::as_str) {
+        Some("coop") => NetMode::Coop,
+        _ => NetMode::Versus,
+    };
+    let port = args.get(1).and_then(|p| p.parse().ok()).unwrap_or(DEFAULT_PORT);
+    let seed = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or_default();
+    let config = ServerConfig {
+        mode,
+        map_size: (16, 16),
+        bomb_count: 40,
+        seed,
+    };
+    if let Err(e) = server::run(("127.0.0.1", port), config) {
+        log::error!("Server failed: {}", e);
+    }
+}
+
+fn run_game(connect: Option<String>) {
     let mut app = App::new();
+    // ウィンドウのタイトルの言語を決めるために先に読み込む
+    let settings = BoardSettings::load(SETTINGS_PATH);
 
     app.add_plugins(DefaultPlugins.set(WindowPlugin {
         primary_window: Some(Window {
-            title: "Mine Sweeper!".to_string(),
+            title: settings.language.text("window.title").to_string(),
             resolution: WindowResolution::new(500f32, 500f32),
             ..Default::default()
         }),
@@ -36,12 +83,46 @@ fn main() {
     #[cfg(feature = "debug")]
     app.add_plugins(WorldInspectorPlugin::new());
 
-    app.insert_state(AppState::Out)
-        .add_plugins(BoardPlugin { running_state: AppState::InGame })
-        .add_systems(Startup, setup_board);
+    #[cfg(feature = "audio")]
+    app.add_plugins(board_plugin::audio::BoardAudioPlugin);
+
+    app.insert_resource(settings)
+        .insert_state(AppState::Out)
+        .add_plugins(BoardPlugin { running_state: AppState::InGame, loading_state: Some(AppState::Loading) })
+        .add_plugins(TitlePlugin { running_state: AppState::Title, game_state: AppState::Loading })
+        .add_systems(Startup, setup_board)
+        .add_systems(Update, start_game.run_if(resource_added::<BoardAssets>));
 
     app.add_systems(Startup, camera_setup);
-    app.add_systems(Update, state_handler);
+    app.add_systems(Update, (
+        state_handler,
+        update_window_title.run_if(resource_changed::<BoardSettings>),
+    ));
+
+    // board plugin option
+    let board_options = BoardOptions {
+        map_size: (20, 20),
+        bomb_count: 40,
+        tile_padding: 3.,
+        safe_start: true,
+        ..Default::default()
+    };
+    match connect {
+        Some(addr) => match NetClient::connect(addr.as_str(), "player") {
+            Ok((client, welcome)) => {
+                log::info!("Connected to {} as player {}", addr, welcome.player);
+                app.insert_resource(welcome.board_options(&board_options))
+                    .insert_resource(client);
+            }
+            Err(e) => {
+                log::error!("Failed to connect to {}: {}", addr, e);
+                return;
+            }
+        },
+        None => {
+            app.insert_resource(board_options);
+        }
+    }
 
     app.run();
 }
@@ -50,73 +131,95 @@ fn camera_setup(mut commands: Commands) {
     commands.spawn(Camera2dBundle::default());
 }
 
-fn my_system(mut commands: Commands) {
-    let entity = commands.spawn_empty();
+fn setup_board(mut commands: Commands, asset_server: Res<AssetServer>) {
+    // Board assets, `BoardAssets` is built once the theme is loaded
+    commands.insert_resource(BoardTheme::builtin(&asset_server));
 }
 
-fn setup_board(
-    mut commands: Commands,
-    mut state: ResMut<NextState<AppState>>,
-    asset_server: Res<AssetServer>,
-) {
-    // board plugin option
-    commands.insert_resource(BoardOptions {
-            map_size: (20, 20),
-            bomb_count: 40,
-            tile_padding: 3.,
-            safe_start: true,
-            ..Default::default()
-        });
-
-    // Board assets
-    commands.insert_resource(BoardAssets {
-        label: "Default".to_string(),
-        board_material: SpriteMaterial {
-            color: Color::WHITE,
-            ..Default::default()
-        },
-        tile_material: SpriteMaterial {
-            color: Color::from(DARK_GRAY),
-            ..Default::default()
-        },
-        covered_tile_material: SpriteMaterial {
-            color: Color::from(GRAY),
-            ..Default::default()
-        },
-        bomb_counter_font: asset_server.load("fonts/GenShinGothic-P-Normal.ttf"),
-        bomb_counter_colors: BoardAssets::default_colors(),
-        flag_material: SpriteMaterial {
-            texture: asset_server.load("sprites/flag.png"),
-            color: Color::from(WHITE),
-        },
-        bomb_material: SpriteMaterial {
-            texture: asset_server.load("sprites/bomb.png"),
-            color: Color::from(WHITE),
-        },
-    });
-
+/// 最初の`BoardAssets`ができたらゲームを始める、タイトルのメニューもこれで描く
+fn start_game(mut state: ResMut<NextState<AppState>>, net_client: Option<Res<NetClient>>) {
     // Plugin activation
-    state.set(AppState::InGame);
+    // 接続していればサーバーのボードですぐに始める
+    if net_client.is_some() {
+        state.set(AppState::Loading);
+    } else {
+        state.set(AppState::Title);
+    }
 }
 
-fn state_handler(state: Res<State<AppState>>, mut next_state: ResMut<NextState<AppState>>, mut inputs: EventReader<KeyboardInput>) {
+#[allow(clippy::too_many_arguments)]
+fn state_handler(
+    state: Res<State<AppState>>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut board_theme: ResMut<BoardTheme>,
+    mut settings: ResMut<BoardSettings>,
+    phase: Option<Res<State<GamePhase>>>,
+    mut next_phase: ResMut<NextState<GamePhase>>,
+    primary: Query<Entity, With<PrimaryBoard>>,
+    mut restart_ewr: EventWriter<RestartBoardEvent>,
+    mut new_board_ewr: EventWriter<NewBoardEvent>,
+    mut inputs: EventReader<KeyboardInput>,
+) {
     for input in inputs.read() {
+        if input.state != ButtonState::Pressed {
+            continue;
+        }
         match input.key_code {
             KeyCode::KeyC => {
                 log::debug!("clearing detected");
                 if state.get() == &AppState::InGame {
                     log::info!("clearing game");
-                    next_state.set(AppState::Out);
+                    next_state.set(AppState::Title);
                 }
             }
             KeyCode::KeyG => {
                 log::debug!("loading detected");
-                if state.get() == &AppState::Out {
+                if state.get() == &AppState::Title {
                     log::info!("loading game");
-                    next_state.set(AppState::InGame);
+                    next_state.set(AppState::Loading);
                 }
             }
+            KeyCode::KeyR => {
+                if let Ok(board) = primary.get_single() {
+                    log::info!("restarting board");
+                    restart_ewr.send(RestartBoardEvent { board });
+                }
+            }
+            KeyCode::KeyN => {
+                if let Ok(board) = primary.get_single() {
+                    log::info!("new board");
+                    new_board_ewr.send(NewBoardEvent { board, options: None });
+                }
+            }
+            KeyCode::KeyT => {
+                board_theme.next();
+                log::info!("switching theme");
+            }
+            KeyCode::Escape => match phase.as_ref().map(|p| *p.get()) {
+                Some(GamePhase::Playing) => next_phase.set(GamePhase::Paused),
+                Some(GamePhase::Paused) => next_phase.set(GamePhase::Playing),
+                _ => {}
+            },
+            KeyCode::KeyP => {
+                settings.palette = settings.palette.next();
+                log::info!("switching palette to {:?}", settings.palette);
+            }
+            KeyCode::KeyL => {
+                settings.language = settings.language.next();
+                log::info!("switching language to {:?}", settings.language);
+            }
+            KeyCode::KeyH => {
+                settings.high_contrast = !settings.high_contrast;
+                log::info!("high contrast {}", if settings.high_contrast { "on" } else { "off" });
+            }
             _ => {}
         }
     }
 }
+
+/// 言語が変わったらウィンドウのタイトルを書き換える
+fn update_window_title(settings: Res<BoardSettings>, mut windows: Query<&mut Window>) {
+    for mut window in windows.iter_mut() {
+        window.title = settings.language.text("window.title").to_string();
+    }
+}
@@ -7,7 +7,7 @@ use {
 #[cfg_attr(feature = "debug", derive(InspectorOptions))]
 #[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Component)]
 pub struct Custom {
-    x: usize,
-    y: usize,
-    bomb_num: usize,
+    pub x: u16,
+    pub y: u16,
+    pub bomb_num: u32,
 }
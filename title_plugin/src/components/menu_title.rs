@@ -0,0 +1,8 @@
+use bevy::prelude::Component;
+#[cfg(feature = "debug")]
+use bevy_inspector_egui::InspectorOptions;
+
+/// タイトルメニューの見出し、言語が変わったら書き換える
+#[cfg_attr(feature = "debug", derive(InspectorOptions))]
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Component)]
+pub struct MenuTitle;
@@ -1,4 +1,8 @@
 pub(crate) mod easy;
 pub(crate) mod normal;
 pub(crate) mod hard;
-pub(crate) mod custom;
\ No newline at end of file
+pub(crate) mod custom;
+pub(crate) mod daily;
+pub(crate) mod flags;
+pub(crate) mod menu;
+pub(crate) mod menu_title;
@@ -1,6 +1,7 @@
 use bevy::prelude::Component;
+#[cfg(feature = "debug")]
 use bevy_inspector_egui::InspectorOptions;
 
 #[cfg_attr(feature = "debug", derive(InspectorOptions))]
 #[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Component)]
-pub struct Normal;
\ No newline at end of file
+pub struct Normal;
@@ -1,10 +1,46 @@
+use bevy::prelude::*;
+use bevy::state::state::FreelyMutableState;
+use board_plugin::resources::settings::BoardSettings;
+use crate::event::{InputDown, InputUp, Submit};
+use crate::systems::menu::{despawn_menu, keyboard_input, relabel_menu, spawn_menu};
+use crate::systems::select_difficulty::{select_difficulty, submit_difficulty};
+
 mod systems;
 mod components;
 mod event;
 mod resources;
 
 
+/// 難易度を選ぶタイトルメニュー
+///
+/// `running_state`の間メニューを表示し、項目を決定すると`BoardOptions`を設定して`game_state`に移る
 pub struct TitlePlugin<T> {
-    running_state: T,
+    pub running_state: T,
+    pub game_state: T,
 }
 
+impl<T: FreelyMutableState> Plugin for TitlePlugin<T> {
+    fn build(&self, app: &mut App) {
+        let game_state = self.game_state.clone();
+        app.add_systems(OnEnter(self.running_state.clone()), spawn_menu)
+            .add_systems(OnExit(self.running_state.clone()), despawn_menu)
+            .add_systems(Update, (
+                keyboard_input,
+                select_difficulty,
+                submit_difficulty,
+                move |mut submit_event_rdr: EventReader<Submit>, mut next_state: ResMut<NextState<T>>| {
+                    if submit_event_rdr.read().count() > 0 {
+                        next_state.set(game_state.clone());
+                    }
+                },
+            ).chain().run_if(in_state(self.running_state.clone())))
+            .add_systems(Update, relabel_menu
+                .run_if(in_state(self.running_state.clone()).and_then(resource_changed::<BoardSettings>)));
+
+        app.add_event::<InputUp>()
+            .add_event::<InputDown>()
+            .add_event::<Submit>();
+
+        log::info!("Loaded Title Plugin");
+    }
+}
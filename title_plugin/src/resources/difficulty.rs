@@ -1,8 +1,57 @@
+use bevy::prelude::Component;
+use board_plugin::resources::daily::DailyChallenge;
+use board_plugin::resources::locale::Language;
 
-
+/// タイトルメニューの項目
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Component)]
 pub enum Difficulty {
     Easy,
     Normal,
     Hard,
     Custom,
-}
\ No newline at end of file
+    /// 日付から決まるボード
+    Daily,
+    /// 2人で交互に爆弾を探す対戦
+    Flags,
+}
+
+impl Difficulty {
+    /// メニューに並べる順
+    pub const ALL: [Difficulty; 6] = [
+        Difficulty::Easy,
+        Difficulty::Normal,
+        Difficulty::Hard,
+        Difficulty::Custom,
+        Difficulty::Daily,
+        Difficulty::Flags,
+    ];
+
+    /// マップの大きさと爆弾の数、カスタムとデイリーは`None`
+    pub fn preset(&self) -> Option<((u16, u16), u32)> {
+        match self {
+            Difficulty::Easy => Some(((9, 9), 10)),
+            Difficulty::Normal | Difficulty::Flags => Some(((16, 16), 40)),
+            Difficulty::Hard => Some(((30, 16), 99)),
+            Difficulty::Custom | Difficulty::Daily => None,
+        }
+    }
+
+    pub fn label(&self, language: Language, daily: &DailyChallenge, streak: u32) -> String {
+        let key = match self {
+            Difficulty::Easy => "menu.easy",
+            Difficulty::Normal => "menu.normal",
+            Difficulty::Hard => "menu.hard",
+            Difficulty::Custom => "menu.custom",
+            Difficulty::Daily => "menu.daily",
+            Difficulty::Flags => "menu.flags",
+        };
+        language.format(key, &[("date", &daily.date), ("streak", &streak)])
+    }
+
+    /// `offset`だけ移動した項目、端で反対側に回り込む
+    pub fn step(&self, offset: isize) -> Self {
+        let len = Self::ALL.len() as isize;
+        let index = Self::ALL.iter().position(|d| d == self).unwrap_or(0) as isize;
+        Self::ALL[(index + offset).rem_euclid(len) as usize]
+    }
+}
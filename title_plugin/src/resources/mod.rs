@@ -1,2 +1,2 @@
-mod difficulty;
-mod option;
\ No newline at end of file
+pub(crate) mod difficulty;
+pub(crate) mod option;
@@ -1,9 +1,12 @@
+use bevy::prelude::Resource;
+use crate::components::custom::Custom;
 use crate::resources::difficulty::Difficulty;
 
+/// タイトルメニューの状態
+#[derive(Debug, Copy, Clone, Resource)]
 pub struct Option {
-    difficulty: Difficulty,
+    /// 選択している項目
+    pub difficulty: Difficulty,
+    /// 起動時の`BoardOptions`のマップ、カスタムを選ぶとこれに戻す
+    pub custom: Custom,
 }
-
-impl Option {
-
-}
\ No newline at end of file
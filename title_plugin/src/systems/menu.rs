@@ -0,0 +1,142 @@
+use bevy::input::keyboard::KeyboardInput;
+use bevy::input::ButtonState;
+use bevy::prelude::*;
+use board_plugin::resources::board_options::BoardOptions;
+use board_plugin::resources::daily::{DailyChallenge, DailyResults};
+use board_plugin::resources::settings::BoardSettings;
+use board_plugin::resources::BoardAssets;
+use crate::components::custom::Custom;
+use crate::components::daily::Daily;
+use crate::components::easy::Easy;
+use crate::components::flags::Flags;
+use crate::components::hard::Hard;
+use crate::components::menu::Menu;
+use crate::components::menu_title::MenuTitle;
+use crate::components::normal::Normal;
+use crate::event::{InputDown, InputUp, Submit};
+use crate::resources::difficulty::Difficulty;
+use crate::resources::option::Option;
+
+/// メニューの項目の文字の大きさ
+const ITEM_FONT_SIZE: f32 = 28.;
+
+/// メニューの項目の間隔
+const ITEM_SPACING: f32 = 40.;
+
+pub fn spawn_menu(
+    mut commands: Commands,
+    board_assets: Res<BoardAssets>,
+    board_options: Res<BoardOptions>,
+    daily_results: Res<DailyResults>,
+    settings: Res<BoardSettings>,
+    option: std::option::Option<Res<Option>>,
+) {
+    let option = match option {
+        Some(o) => *o,
+        None => {
+            let option = Option {
+                difficulty: Difficulty::Easy,
+                custom: Custom {
+                    x: board_options.map_size.0,
+                    y: board_options.map_size.1,
+                    bomb_num: board_options.bomb_count,
+                },
+            };
+            commands.insert_resource(option);
+            option
+        }
+    };
+    let language = settings.language;
+    let daily = DailyChallenge::today();
+    let streak = daily_results.streak(daily.date);
+    let style = TextStyle {
+        font: board_assets.bomb_counter_font.clone(),
+        font_size: ITEM_FONT_SIZE,
+        color: Color::WHITE,
+    };
+    let top = (Difficulty::ALL.len() as f32 - 1.) * ITEM_SPACING / 2.;
+    commands
+        .spawn(SpatialBundle::default())
+        .insert(Name::new("Menu"))
+        .insert(Menu)
+        .with_children(|parent| {
+            parent.spawn(Text2dBundle {
+                text: Text::from_section(language.text("menu.title"), TextStyle {
+                    font_size: ITEM_FONT_SIZE * 1.5,
+                    ..style.clone()
+                }),
+                transform: Transform::from_xyz(0., top + ITEM_SPACING * 1.5, 1.),
+                ..Default::default()
+            }).insert(MenuTitle);
+            for (i, difficulty) in Difficulty::ALL.into_iter().enumerate() {
+                let mut cmd = parent.spawn(Text2dBundle {
+                    text: Text::from_section(difficulty.label(language, &daily, streak), style.clone()),
+                    transform: Transform::from_xyz(0., top - i as f32 * ITEM_SPACING, 1.),
+                    ..Default::default()
+                });
+                cmd.insert(difficulty);
+                match difficulty {
+                    Difficulty::Easy => cmd.insert(Easy),
+                    Difficulty::Normal => cmd.insert(Normal),
+                    Difficulty::Hard => cmd.insert(Hard),
+                    Difficulty::Custom => cmd.insert(option.custom),
+                    Difficulty::Daily => cmd.insert(Daily),
+                    Difficulty::Flags => cmd.insert(Flags),
+                };
+            }
+        });
+}
+
+/// 言語が変わったら見出しと項目を書き換える
+pub fn relabel_menu(
+    settings: Res<BoardSettings>,
+    daily_results: Res<DailyResults>,
+    mut titles: Query<&mut Text, (With<MenuTitle>, Without<Difficulty>)>,
+    mut items: Query<(&Difficulty, &mut Text), Without<MenuTitle>>,
+) {
+    let language = settings.language;
+    let daily = DailyChallenge::today();
+    let streak = daily_results.streak(daily.date);
+    for mut text in titles.iter_mut() {
+        if let Some(section) = text.sections.first_mut() {
+            section.value = language.text("menu.title").to_string();
+        }
+    }
+    for (difficulty, mut text) in items.iter_mut() {
+        if let Some(section) = text.sections.first_mut() {
+            section.value = difficulty.label(language, &daily, streak);
+        }
+    }
+}
+
+pub fn despawn_menu(mut commands: Commands, menus: Query<Entity, With<Menu>>) {
+    for entity in menus.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+/// キーボードの入力をメニューの操作に変換する
+pub fn keyboard_input(
+    mut inputs: EventReader<KeyboardInput>,
+    mut up_event_wr: EventWriter<InputUp>,
+    mut down_event_wr: EventWriter<InputDown>,
+    mut submit_event_wr: EventWriter<Submit>,
+) {
+    for input in inputs.read() {
+        if input.state != ButtonState::Pressed {
+            continue;
+        }
+        match input.key_code {
+            KeyCode::ArrowUp | KeyCode::KeyW => {
+                up_event_wr.send(InputUp);
+            }
+            KeyCode::ArrowDown | KeyCode::KeyS => {
+                down_event_wr.send(InputDown);
+            }
+            KeyCode::Enter | KeyCode::Space => {
+                submit_event_wr.send(Submit);
+            }
+            _ => {}
+        }
+    }
+}
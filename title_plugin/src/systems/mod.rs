@@ -1 +1,2 @@
-mod select_difficulty;
\ No newline at end of file
+pub(crate) mod menu;
+pub(crate) mod select_difficulty;
@@ -1,18 +1,60 @@
-use bevy::prelude::{EventReader, ResMut};
-use board_plugin::resources::board_options::BoardOptions;
+use bevy::color::palettes::css::{WHITE, YELLOW};
+use bevy::prelude::*;
+use board_plugin::resources::board_options::{BoardOptions, GameMode};
+use board_plugin::resources::daily::DailyChallenge;
 use crate::event::{InputDown, InputUp, Submit};
+use crate::resources::difficulty::Difficulty;
+use crate::resources::option::Option;
 
+/// 選んだ項目のボード生成オプションを設定する
+///
+/// デイリーチャレンジは`BoardOptions`を書き換えず、ボードを作るときにその日の設定にする
 pub fn submit_difficulty(
-
+    mut commands: Commands,
     mut board_option: ResMut<BoardOptions>,
+    option: Res<Option>,
     mut submit_event_rdr: EventReader<Submit>,
 ) {
-
+    if submit_event_rdr.read().count() == 0 {
+        return;
+    }
+    log::info!("Selected {:?}", option.difficulty);
+    if option.difficulty == Difficulty::Daily {
+        commands.insert_resource(DailyChallenge::today());
+        return;
+    }
+    commands.remove_resource::<DailyChallenge>();
+    let (map_size, bomb_count) = option.difficulty.preset().unwrap_or((
+        (option.custom.x, option.custom.y),
+        option.custom.bomb_num,
+    ));
+    board_option.map_size = map_size;
+    board_option.bomb_count = bomb_count;
+    board_option.seed = None;
+    board_option.mode = match option.difficulty {
+        Difficulty::Flags => GameMode::Flags,
+        _ => GameMode::Classic,
+    };
 }
 
+/// 上下の入力で項目を選び、選んでいる項目の色を変える
 pub fn select_difficulty(
+    mut option: ResMut<Option>,
     mut up_event_rdr: EventReader<InputUp>,
     mut down_event_rdr: EventReader<InputDown>,
+    mut items: Query<(&Difficulty, &mut Text)>,
 ) {
-
-}
\ No newline at end of file
+    let offset = down_event_rdr.read().count() as isize - up_event_rdr.read().count() as isize;
+    if offset != 0 {
+        option.difficulty = option.difficulty.step(offset);
+    }
+    if offset == 0 && !option.is_changed() {
+        return;
+    }
+    for (difficulty, mut text) in items.iter_mut() {
+        let color = if *difficulty == option.difficulty { YELLOW } else { WHITE };
+        for section in text.sections.iter_mut() {
+            section.style.color = Color::from(color);
+        }
+    }
+}